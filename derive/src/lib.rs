@@ -0,0 +1,372 @@
+//! `#[derive(FromInput)]`, generating `steinerkelvin_aoc_2023::parser::FromInput`
+//! impls from format strings. Written against the bare `proc_macro` API so that
+//! the workspace keeps no dependencies.
+//!
+//! On a struct, `#[format("Game {id}: {sets}")]` lists the fields in input
+//! order. Text between fields must match exactly, except for whitespace in the
+//! format. Between two fields, or a field and a letter or digit, it matches a
+//! run of at least one whitespace character; next to punctuation, as after the
+//! colon above, it matches any run, including none. Fields are parsed with
+//! their own `FromInput` impls; a `Vec` field needs `#[sep(";")]`, and a
+//! `Vec<Vec<_>>` field `#[sep(";", ",")]`, outermost separator first.
+//!
+//! On an enum of unit variants, each variant matches its name in lowercase, or
+//! the text of its own `#[format("...")]`.
+
+use proc_macro::{Delimiter, Spacing, TokenStream, TokenTree};
+
+const PARSER: &str = "::steinerkelvin_aoc_2023::parser";
+
+#[proc_macro_derive(FromInput, attributes(format, sep))]
+pub fn derive_from_input(input: TokenStream) -> TokenStream {
+    let code = match derive(input) {
+        Ok(code) => code,
+        Err(message) => format!("compile_error!({:?});", message),
+    };
+    code.parse().expect("generated code is valid Rust")
+}
+
+#[derive(Default)]
+struct Attrs {
+    format: Option<String>,
+    seps: Vec<String>,
+}
+
+enum Step {
+    Text(String),
+    Ws,
+    Field(String),
+}
+
+fn derive(input: TokenStream) -> Result<String, String> {
+    let tokens: Vec<TokenTree> = input.into_iter().collect();
+    let (attrs, rest) = take_attrs(&tokens)?;
+    let rest = skip_visibility(rest);
+    let (kind, name, body) = match rest {
+        [TokenTree::Ident(kind), TokenTree::Ident(name), TokenTree::Group(body)]
+            if body.delimiter() == Delimiter::Brace =>
+        {
+            (kind.to_string(), name.to_string(), body.stream())
+        }
+        [TokenTree::Ident(_), TokenTree::Ident(name), ..] => {
+            return Err(format!(
+                "FromInput on {}: only structs with named fields and enums without generics are supported",
+                name
+            ))
+        }
+        _ => return Err("FromInput: expected a struct or enum".to_string()),
+    };
+    let body: Vec<TokenTree> = body.into_iter().collect();
+    match kind.as_str() {
+        "struct" => derive_struct(&name, attrs, &body),
+        "enum" => derive_enum(&name, &body),
+        _ => Err(format!("FromInput: cannot derive for {} {}", kind, name)),
+    }
+}
+
+fn derive_struct(name: &str, attrs: Attrs, body: &[TokenTree]) -> Result<String, String> {
+    let format = attrs
+        .format
+        .ok_or_else(|| format!("FromInput on {}: missing #[format(\"...\")]", name))?;
+    let mut fields = Vec::new();
+    for field in split_commas(body) {
+        let (attrs, rest) = take_attrs(field)?;
+        match skip_visibility(rest) {
+            [TokenTree::Ident(field), TokenTree::Punct(colon), ..] if colon.as_char() == ':' => {
+                fields.push((field.to_string(), attrs.seps));
+            }
+            _ => return Err(format!("FromInput on {}: expected named fields", name)),
+        }
+    }
+
+    let steps = parse_format(&format)?;
+    let mut code = String::new();
+    let mut seen = Vec::new();
+    for (i, step) in steps.iter().enumerate() {
+        match step {
+            Step::Text(text) => code.push_str(&format!(
+                "let (__cursor, _) = {}::exact(__cursor, {:?})?;\n",
+                PARSER, text
+            )),
+            Step::Ws => {
+                let word_before = i > 0 && is_word(&steps[i - 1], |text| text.chars().last());
+                let word_after = steps
+                    .get(i + 1)
+                    .is_some_and(|s| is_word(s, |text| text.chars().next()));
+                let skip = if word_before && word_after {
+                    "require_whitespace"
+                } else {
+                    "skip_whitespace"
+                };
+                code.push_str(&format!(
+                    "let (__cursor, _) = {}::{}(__cursor)?;\n",
+                    PARSER, skip
+                ))
+            }
+            Step::Field(field) => {
+                let Some((_, seps)) = fields.iter().find(|(name, _)| name == field) else {
+                    return Err(format!("FromInput on {}: no field {:?}", name, field));
+                };
+                if seen.contains(field) {
+                    return Err(format!(
+                        "FromInput on {}: field {:?} appears twice",
+                        name, field
+                    ));
+                }
+                seen.push(field.clone());
+                code.push_str(&format!(
+                    "let (__cursor, {}) = {}?;\n",
+                    field,
+                    field_parser(seps)
+                ));
+            }
+        }
+    }
+    if let Some((missing, _)) = fields.iter().find(|(field, _)| !seen.contains(field)) {
+        return Err(format!(
+            "FromInput on {}: field {:?} is not in the format",
+            name, missing
+        ));
+    }
+
+    let field_names: Vec<&str> = fields.iter().map(|(field, _)| field.as_str()).collect();
+    Ok(format!(
+        "impl {p}::FromInput for {name} {{
+            fn from_input<'a>(parser: {p}::Parser<'a>) -> {p}::ParserResult<'a, Self> {{
+                {p}::rule({name:?}, \"\", parser, |__cursor| {{
+                    {code}
+                    __cursor.with(Self {{ {fields} }})
+                }})
+            }}
+        }}",
+        p = PARSER,
+        name = name,
+        code = code,
+        fields = field_names.join(", "),
+    ))
+}
+
+/// The parser for a field: its own `FromInput`, inside one `parse_list` per
+/// separator.
+fn field_parser(seps: &[String]) -> String {
+    let Some((outer, inner)) = seps.split_first() else {
+        return format!("{}::FromInput::from_input(__cursor)", PARSER);
+    };
+    let mut item = format!("{}::FromInput::from_input", PARSER);
+    for sep in inner.iter().rev() {
+        item = format!(
+            "|__cursor| {}::parse_list(__cursor, {:?}, {})",
+            PARSER, sep, item
+        );
+    }
+    format!("{}::parse_list(__cursor, {:?}, {})", PARSER, outer, item)
+}
+
+fn derive_enum(name: &str, body: &[TokenTree]) -> Result<String, String> {
+    let mut variants = Vec::new();
+    for variant in split_commas(body) {
+        let (attrs, rest) = take_attrs(variant)?;
+        match rest {
+            [TokenTree::Ident(variant)] => {
+                let variant = variant.to_string();
+                let text = attrs.format.unwrap_or_else(|| variant.to_lowercase());
+                variants.push((variant, text));
+            }
+            _ => {
+                return Err(format!(
+                    "FromInput on {}: only unit variants are supported",
+                    name
+                ))
+            }
+        }
+    }
+    // Longest first, so that no variant is shadowed by a prefix of its text
+    variants.sort_by_key(|(_, text)| std::cmp::Reverse(text.len()));
+
+    let mut code = String::new();
+    for (variant, text) in &variants {
+        code.push_str(&format!(
+            "if let Some((__cursor, _)) = {}::exact(__cursor, {:?}) {{
+                return __cursor.with(Self::{});
+            }}\n",
+            PARSER, text, variant
+        ));
+    }
+    Ok(format!(
+        "impl {p}::FromInput for {name} {{
+            fn from_input<'a>(parser: {p}::Parser<'a>) -> {p}::ParserResult<'a, Self> {{
+                {p}::rule({name:?}, \"\", parser, |__cursor| {{
+                    {code}
+                    None
+                }})
+            }}
+        }}",
+        p = PARSER,
+        name = name,
+        code = code,
+    ))
+}
+
+// Token helpers
+
+/// Reads the leading `#[...]` attributes, keeping `format` and `sep`.
+fn take_attrs(tokens: &[TokenTree]) -> Result<(Attrs, &[TokenTree]), String> {
+    let mut attrs = Attrs::default();
+    let mut rest = tokens;
+    while let [TokenTree::Punct(hash), TokenTree::Group(group), tail @ ..] = rest {
+        if hash.as_char() != '#' || group.delimiter() != Delimiter::Bracket {
+            break;
+        }
+        rest = tail;
+        let inner: Vec<TokenTree> = group.stream().into_iter().collect();
+        let [TokenTree::Ident(attr), TokenTree::Group(args)] = inner.as_slice() else {
+            continue;
+        };
+        let args: Vec<TokenTree> = args.stream().into_iter().collect();
+        match attr.to_string().as_str() {
+            "format" => match args.as_slice() {
+                [TokenTree::Literal(lit)] => attrs.format = Some(string_literal(&lit.to_string())?),
+                _ => return Err("#[format] expects one string literal".to_string()),
+            },
+            "sep" => {
+                for arg in split_commas(&args) {
+                    match arg {
+                        [TokenTree::Literal(lit)] => {
+                            attrs.seps.push(string_literal(&lit.to_string())?)
+                        }
+                        _ => return Err("#[sep] expects string literals".to_string()),
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok((attrs, rest))
+}
+
+fn skip_visibility(tokens: &[TokenTree]) -> &[TokenTree] {
+    match tokens {
+        [TokenTree::Ident(vis), TokenTree::Group(group), rest @ ..]
+            if vis.to_string() == "pub" && group.delimiter() == Delimiter::Parenthesis =>
+        {
+            rest
+        }
+        [TokenTree::Ident(vis), rest @ ..] if vis.to_string() == "pub" => rest,
+        _ => tokens,
+    }
+}
+
+/// Splits on top-level commas, not counting those inside `<...>`. The `>` of
+/// an `->` closes nothing.
+fn split_commas(tokens: &[TokenTree]) -> Vec<&[TokenTree]> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, token) in tokens.iter().enumerate() {
+        if let TokenTree::Punct(punct) = token {
+            let arrow = i > 0
+                && matches!(&tokens[i - 1], TokenTree::Punct(prev)
+                    if prev.as_char() == '-' && prev.spacing() == Spacing::Joint);
+            match punct.as_char() {
+                '<' => depth += 1,
+                '>' if !arrow => depth -= 1,
+                ',' if depth == 0 => {
+                    parts.push(&tokens[start..i]);
+                    start = i + 1;
+                }
+                _ => {}
+            }
+        }
+    }
+    parts.push(&tokens[start..]);
+    parts.retain(|part| !part.is_empty());
+    parts
+}
+
+/// The value of a (possibly raw) string literal token.
+fn string_literal(lit: &str) -> Result<String, String> {
+    if let Some(raw) = lit.strip_prefix('r') {
+        let raw = raw.trim_matches('#');
+        return Ok(raw[1..raw.len() - 1].to_string());
+    }
+    let Some(quoted) = lit.strip_prefix('"').and_then(|lit| lit.strip_suffix('"')) else {
+        return Err(format!("expected a string literal, found {}", lit));
+    };
+    let mut value = String::new();
+    let mut chars = quoted.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            value.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => value.push('\n'),
+            Some('t') => value.push('\t'),
+            Some('r') => value.push('\r'),
+            Some('0') => value.push('\0'),
+            Some(c @ ('\\' | '"' | '\'')) => value.push(c),
+            _ => return Err(format!("unsupported escape in {}", lit)),
+        }
+    }
+    Ok(value)
+}
+
+/// Whether `step` is a field, or text whose `edge` character is a letter or
+/// digit, so that whitespace next to it cannot be left out.
+fn is_word(step: &Step, edge: fn(&str) -> Option<char>) -> bool {
+    match step {
+        Step::Text(text) => edge(text).is_some_and(char::is_alphanumeric),
+        Step::Ws => false,
+        Step::Field(_) => true,
+    }
+}
+
+/// Splits a format into literal text, whitespace, and `{field}` steps. `{{`
+/// and `}}` stand for literal braces.
+fn parse_format(format: &str) -> Result<Vec<Step>, String> {
+    let mut steps = Vec::new();
+    let mut text = String::new();
+    let mut chars = format.chars().peekable();
+    let flush = |text: &mut String, steps: &mut Vec<Step>| {
+        if !text.is_empty() {
+            steps.push(Step::Text(std::mem::take(text)));
+        }
+    };
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                text.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                text.push('}');
+            }
+            '{' => {
+                flush(&mut text, &mut steps);
+                let mut field = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => field.push(c),
+                        None => return Err(format!("unclosed '{{' in format {:?}", format)),
+                    }
+                }
+                if field.trim().is_empty() {
+                    return Err(format!("empty field in format {:?}", format));
+                }
+                steps.push(Step::Field(field.trim().to_string()));
+            }
+            '}' => return Err(format!("unmatched '}}' in format {:?}", format)),
+            c if c.is_whitespace() => {
+                flush(&mut text, &mut steps);
+                if !matches!(steps.last(), Some(Step::Ws)) {
+                    steps.push(Step::Ws);
+                }
+            }
+            c => text.push(c),
+        }
+    }
+    flush(&mut text, &mut steps);
+    Ok(steps)
+}
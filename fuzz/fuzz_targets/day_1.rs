@@ -0,0 +1,19 @@
+//! Day 1 never panics: any input gives an answer or an error, and both part 1
+//! implementations agree on which.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use steinerkelvin_aoc_2023::day_1::day_1_trebuchet_part_2;
+use steinerkelvin_aoc_2023::day_1::{day_1_trebuchet, day_1_trebuchet_bytes};
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(body) = std::str::from_utf8(data) else {
+        return;
+    };
+    // A narrow accumulator, so that overflow is reachable
+    let chars = day_1_trebuchet::<u32>(body).map_err(|err| err.to_string());
+    let bytes = day_1_trebuchet_bytes::<u32>(body).map_err(|err| err.to_string());
+    assert_eq!(chars, bytes);
+    let _ = day_1_trebuchet_part_2::<u32>(body);
+});
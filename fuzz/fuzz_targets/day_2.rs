@@ -0,0 +1,38 @@
+//! Day 2 never panics: any input parses into games or gives an error, and the
+//! games answer both parts or overflow. Printed games also parse back equal,
+//! recovering from errors agrees with the strict parse on valid input and
+//! otherwise gives a game or an error for every non-blank line, and the
+//! borrowed parse accepts the same inputs and gives the same answers.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use steinerkelvin_aoc_2023::day_2::DEFAULT_BAG;
+use steinerkelvin_aoc_2023::day_2::{bag_from_str, day_2_cube_conundrum, parse_input};
+use steinerkelvin_aoc_2023::day_2::{day_2_borrowed, day_2_borrowed_part_2, Games};
+use steinerkelvin_aoc_2023::day_2::{day_2_cube_conundrum_part_2, parse_input_recovering};
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(body) = std::str::from_utf8(data) else {
+        return;
+    };
+    let (recovered, errors) = parse_input_recovering(body);
+    let borrowed = Games::parse(body);
+    let Ok(games) = parse_input(body) else {
+        let lines = body.lines().filter(|line| !line.trim().is_empty());
+        assert_eq!(recovered.len() + errors.len(), lines.count());
+        assert_eq!(borrowed.err(), parse_input(body).err());
+        return;
+    };
+    let borrowed = borrowed.unwrap();
+    assert!(errors.is_empty());
+    assert_eq!(recovered, games);
+    let bag = bag_from_str(DEFAULT_BAG).unwrap();
+    let part_1 = day_2_cube_conundrum::<u32>(&games, &bag);
+    assert_eq!(part_1, day_2_borrowed::<u32>(&borrowed, &bag));
+    let _ = day_2_cube_conundrum_part_2::<u32>(&games);
+    let _ = day_2_borrowed_part_2::<u32>(&borrowed);
+
+    let printed: Vec<String> = games.iter().map(|game| game.to_string()).collect();
+    assert_eq!(parse_input(&printed.join("\n")), Ok(games));
+});
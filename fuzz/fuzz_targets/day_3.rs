@@ -0,0 +1,37 @@
+//! Day 3 never panics: any input parses into a schematic or gives an error,
+//! and the schematic answers both parts or overflows, and renders. The
+//! parallel parser agrees with the sequential one.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use steinerkelvin_aoc_2023::day_3::{day_3_gear_ratios, day_3_gear_ratios_part_2};
+use steinerkelvin_aoc_2023::day_3::{Arity, GearRule, RaggedRows, Reduction, Schematic};
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(input) = std::str::from_utf8(data) else {
+        return;
+    };
+    let _ = day_3_gear_ratios::<u32>(input);
+    let _ = day_3_gear_ratios_part_2::<u32>(input, &GearRule::puzzle());
+
+    for ragged in [RaggedRows::Reject, RaggedRows::Pad] {
+        let sequential = Schematic::parse_with(input, ragged);
+        let parallel = Schematic::parse_parallel(input, ragged, 3);
+        assert_eq!(sequential, parallel);
+        let Ok(schematic) = sequential else {
+            continue;
+        };
+        let rule = GearRule {
+            symbols: b"*#".to_vec(),
+            arity: Arity::AtLeast(1),
+            reduction: Reduction::Sum,
+        };
+        let _ = schematic.gears::<u32>(&rule);
+        let _ = schematic.flag_matrix();
+        let _ = schematic.to_dot();
+        let _ = schematic.to_json();
+        let _ = schematic.render(input, &rule, true);
+        let _ = schematic.render(input, &rule, false);
+    }
+});
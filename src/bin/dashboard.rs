@@ -0,0 +1,437 @@
+/* Dashboard over every day of the season
+
+Lists each part of every day in the registry with its status, when it last ran
+and how long it took, and its answer. A part is unsolved until it runs, failed
+if it gives an error or an answer other than the accepted one, verified if it
+gives the accepted one, and solved if there is no accepted answer yet.
+
+  j, k, arrows   select a part
+  enter, r       run the selected day
+  a              run every day
+  i              show the day's input
+  e              run the day's examples and show them
+  v              show the day's views, such as the Day 3 schematic
+  q, esc         go back, or quit
+
+Pages scroll with j, k, space and b. The terminal is put in non-canonical mode
+through `stty`, and restored on exit. When stdin or stdout is not a terminal,
+every day and example runs once and the table is printed instead, failing if
+anything did.
+*/
+
+use std::io::{IsTerminal, Read, Write};
+use std::process::{Command, Stdio};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use steinerkelvin_aoc_2023::day_3::{GREEN, RED, RESET};
+use steinerkelvin_aoc_2023::registry::{Day, Run, Status, DAYS};
+
+const BOLD: &str = "\x1b[1m";
+const DIM: &str = "\x1b[2m";
+const REVERSE: &str = "\x1b[7m";
+const CYAN: &str = "\x1b[36m";
+
+fn status_name(status: Status) -> &'static str {
+    match status {
+        Status::Unsolved => "unsolved",
+        Status::Failed => "failed",
+        Status::Solved => "solved",
+        Status::Verified => "verified",
+    }
+}
+
+fn status_color(status: Status) -> &'static str {
+    match status {
+        Status::Unsolved => DIM,
+        Status::Failed => RED,
+        Status::Solved => CYAN,
+        Status::Verified => GREEN,
+    }
+}
+
+/// The time of day of `at` in UTC, as `hh:mm:ss`.
+fn clock(at: SystemTime) -> String {
+    let secs = at.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs()) % 86_400;
+    format!("{:02}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+}
+
+/// One row of the table, without colours.
+fn row(day: &Day, part: usize, run: Option<&Run>) -> (Status, String) {
+    let status = day.parts[part].status(run);
+    let (last, answer) = match run {
+        None => (String::new(), String::new()),
+        Some(run) => (
+            format!("{} in {:.2?}", clock(run.at), run.elapsed),
+            match &run.answer {
+                Ok(answer) => answer.clone(),
+                Err(err) => format!("error: {}", err),
+            },
+        ),
+    };
+    let line = format!(
+        "{:>3}  {:<16} {:>4}  {:<9} {:<22} {}",
+        day.number,
+        day.title,
+        part + 1,
+        status_name(status),
+        last,
+        answer
+    );
+    (status, line)
+}
+
+const HEADER: &str = "day  title            part  status    last run               answer";
+
+/// Runs every example of `day`, as lines for a page.
+fn examples(day: &Day) -> Vec<String> {
+    let mut lines = Vec::new();
+    for (number, example) in day.examples.iter().enumerate() {
+        let result = match example.check(day) {
+            Ok(()) => format!("{}ok{}, {}", GREEN, RESET, example.answer),
+            Err(err) => format!("{}failed{}, {}", RED, RESET, err),
+        };
+        lines.push(format!(
+            "{}Example {}, part {}{}: {}",
+            BOLD,
+            number + 1,
+            example.part,
+            RESET,
+            result
+        ));
+        lines.extend(example.input.lines().map(|line| format!("  {}", line)));
+        lines.push(String::new());
+    }
+    if lines.is_empty() {
+        lines.push("No examples".to_string());
+    }
+    lines
+}
+
+/// Cuts `line` down to `width` visible characters, skipping escape sequences.
+fn clip(line: &str, width: usize) -> String {
+    let mut clipped = String::new();
+    let mut visible = 0;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            clipped.push(c);
+            for c in chars.by_ref() {
+                clipped.push(c);
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else if visible < width {
+            clipped.push(c);
+            visible += 1;
+        }
+    }
+    clipped + RESET
+}
+
+fn stty(args: &[&str]) -> Option<String> {
+    let output = Command::new("stty")
+        .args(args)
+        .stdin(Stdio::inherit())
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Rows and columns of the terminal.
+fn size() -> (usize, usize) {
+    let size = stty(&["size"]).unwrap_or_default();
+    let mut words = size.split(' ').filter_map(|word| word.parse().ok());
+    match (words.next(), words.next()) {
+        (Some(rows), Some(cols)) if rows > 0 && cols > 0 => (rows, cols),
+        _ => (24, 80),
+    }
+}
+
+/// Keeps the terminal on the alternate screen in non-canonical mode, until
+/// dropped.
+struct Terminal {
+    saved: String,
+}
+
+impl Terminal {
+    fn enter() -> Result<Terminal, Box<dyn std::error::Error>> {
+        let saved = stty(&["-g"]).ok_or("Cannot read the terminal settings")?;
+        stty(&["-icanon", "-echo", "min", "0", "time", "1"])
+            .ok_or("Cannot change the terminal settings")?;
+        print!("\x1b[?1049h\x1b[?25l");
+        Ok(Terminal { saved })
+    }
+}
+
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        print!("\x1b[?25h\x1b[?1049l");
+        let _ = std::io::stdout().flush();
+        stty(&[&self.saved]);
+    }
+}
+
+#[derive(PartialEq, Eq)]
+enum Key {
+    Up,
+    Down,
+    Enter,
+    Escape,
+    Char(char),
+}
+
+/// Waits for a key. A lone escape is one not followed by more input within
+/// the terminal's read timeout.
+fn read_key() -> std::io::Result<Option<Key>> {
+    let stdin = &mut std::io::stdin();
+    let mut byte = [0];
+    loop {
+        if stdin.read(&mut byte)? == 1 {
+            break;
+        }
+    }
+    let key = match byte[0] {
+        b'\n' | b'\r' => Key::Enter,
+        0x1b => {
+            let mut sequence = [0; 2];
+            let mut read = stdin.read(&mut sequence)?;
+            if read == 0 {
+                return Ok(Some(Key::Escape));
+            }
+            if read == 1 {
+                read += stdin.read(&mut sequence[1..])?;
+            }
+            match (read, sequence) {
+                (2, [b'[', b'A']) => Key::Up,
+                (2, [b'[', b'B']) => Key::Down,
+                _ => return Ok(None),
+            }
+        }
+        byte if byte.is_ascii() => Key::Char(byte as char),
+        _ => return Ok(None),
+    };
+    Ok(Some(key))
+}
+
+enum Screen {
+    Table,
+    Page {
+        title: String,
+        lines: Vec<String>,
+        top: usize,
+    },
+    Views {
+        day: usize,
+        selected: usize,
+    },
+}
+
+struct Dashboard {
+    runs: Vec<[Option<Run>; 2]>,
+    selected: usize,
+    message: String,
+    /// Screens below the current one, to go back to.
+    stack: Vec<Screen>,
+    screen: Screen,
+}
+
+impl Dashboard {
+    fn run(&mut self, day: usize) {
+        for part in 0..2 {
+            self.runs[day][part] = Some(DAYS[day].parts[part].run(DAYS[day].input));
+        }
+        self.message = format!("Ran day {}", DAYS[day].number);
+    }
+
+    fn open(&mut self, screen: Screen) {
+        self.stack.push(std::mem::replace(&mut self.screen, screen));
+    }
+
+    fn page(&mut self, title: String, lines: Vec<String>) {
+        self.open(Screen::Page {
+            title,
+            lines,
+            top: 0,
+        });
+    }
+
+    fn draw(&self) -> String {
+        let (rows, cols) = size();
+        let mut out = vec![];
+        match &self.screen {
+            Screen::Table => {
+                out.push(format!("{}{}{}", BOLD, HEADER, RESET));
+                for (index, (day, runs)) in DAYS.iter().zip(&self.runs).enumerate() {
+                    for (part, run) in runs.iter().enumerate() {
+                        let (status, line) = row(day, part, run.as_ref());
+                        let line = clip(&line, cols);
+                        if index * 2 + part == self.selected {
+                            out.push(format!("{}{}", REVERSE, line));
+                        } else {
+                            out.push(format!("{}{}", status_color(status), line));
+                        }
+                    }
+                }
+                out.push(String::new());
+                out.push(self.message.clone());
+                out.push(format!(
+                    "{}enter run  a run all  i input  e examples  v views  q quit{}",
+                    DIM, RESET
+                ));
+            }
+            Screen::Page { title, lines, top } => {
+                out.push(format!("{}{}{}", BOLD, title, RESET));
+                let height = rows.saturating_sub(2);
+                out.extend(
+                    lines
+                        .iter()
+                        .skip(*top)
+                        .take(height)
+                        .map(|line| clip(line, cols)),
+                );
+                out.resize(height + 1, String::new());
+                out.push(format!(
+                    "{}lines {}-{} of {}  j/k scroll  space/b page  q back{}",
+                    DIM,
+                    (top + 1).min(lines.len()),
+                    (top + height).min(lines.len()),
+                    lines.len(),
+                    RESET
+                ));
+            }
+            Screen::Views { day, selected } => {
+                let day = &DAYS[*day];
+                out.push(format!("{}Views of day {}{}", BOLD, day.number, RESET));
+                for (index, view) in day.views.iter().enumerate() {
+                    let marker = if index == *selected { REVERSE } else { "" };
+                    out.push(format!("{}{}{}", marker, view.name, RESET));
+                }
+                out.push(String::new());
+                out.push(format!("{}enter open  q back{}", DIM, RESET));
+            }
+        }
+        out.truncate(rows);
+        format!("\x1b[H\x1b[2J{}", out.join("\r\n"))
+    }
+
+    /// Handles `key`, giving whether to quit.
+    fn key(&mut self, key: Key) -> bool {
+        let (rows, _) = size();
+        let back = key == Key::Escape || key == Key::Char('q');
+        let day = self.selected / 2;
+        match &mut self.screen {
+            Screen::Table => match key {
+                _ if back => return true,
+                Key::Up | Key::Char('k') => self.selected = self.selected.saturating_sub(1),
+                Key::Down | Key::Char('j') => {
+                    self.selected = (self.selected + 1).min(DAYS.len() * 2 - 1)
+                }
+                Key::Enter | Key::Char('r') => self.run(day),
+                Key::Char('a') => {
+                    (0..DAYS.len()).for_each(|day| self.run(day));
+                    self.message = "Ran every day".to_string();
+                }
+                Key::Char('i') => {
+                    let lines = DAYS[day].input.lines().map(str::to_string).collect();
+                    self.page(format!("Input of day {}", DAYS[day].number), lines);
+                }
+                Key::Char('e') => {
+                    let title = format!("Examples of day {}", DAYS[day].number);
+                    self.page(title, examples(&DAYS[day]));
+                }
+                Key::Char('v') if DAYS[day].views.is_empty() => {
+                    self.message = format!("Day {} has no views", DAYS[day].number);
+                }
+                Key::Char('v') => self.open(Screen::Views { day, selected: 0 }),
+                _ => {}
+            },
+            Screen::Page { lines, top, .. } => {
+                let height = rows.saturating_sub(2).max(1);
+                let last = lines.len().saturating_sub(height);
+                *top = match key {
+                    Key::Up | Key::Char('k') => top.saturating_sub(1),
+                    Key::Down | Key::Char('j') => *top + 1,
+                    Key::Char(' ') => *top + height,
+                    Key::Char('b') => top.saturating_sub(height),
+                    _ => *top,
+                }
+                .min(last);
+            }
+            Screen::Views { day, selected } => match key {
+                Key::Up | Key::Char('k') => *selected = selected.saturating_sub(1),
+                Key::Down | Key::Char('j') => {
+                    *selected = (*selected + 1).min(DAYS[*day].views.len() - 1)
+                }
+                Key::Enter => {
+                    let (day, view) = (&DAYS[*day], &DAYS[*day].views[*selected]);
+                    let lines = match (view.render)(day.input) {
+                        Ok(text) => text.lines().map(str::to_string).collect(),
+                        Err(err) => vec![format!("error: {}", err)],
+                    };
+                    self.page(format!("Day {}, {}", day.number, view.name), lines);
+                }
+                _ => {}
+            },
+        }
+        if back {
+            if let Some(screen) = self.stack.pop() {
+                self.screen = screen;
+            }
+        }
+        false
+    }
+}
+
+/// Runs everything once and prints the table, for when there is no terminal.
+fn report() -> Result<(), Box<dyn std::error::Error>> {
+    let mut failed = 0;
+    println!("{}", HEADER);
+    for day in DAYS {
+        for (part, solver) in day.parts.iter().enumerate() {
+            let run = solver.run(day.input);
+            let (status, line) = row(day, part, Some(&run));
+            failed += (status == Status::Failed) as usize;
+            println!("{}", line);
+        }
+    }
+    for day in DAYS {
+        for (number, example) in day.examples.iter().enumerate() {
+            if let Err(err) = example.check(day) {
+                failed += 1;
+                println!("day {} example {}: {}", day.number, number + 1, err);
+            }
+        }
+    }
+    match failed {
+        0 => Ok(()),
+        _ => Err(format!("{} failed", failed).into()),
+    }
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    if !std::io::stdin().is_terminal() || !std::io::stdout().is_terminal() {
+        return report();
+    }
+
+    let _terminal = Terminal::enter()?;
+    let mut dashboard = Dashboard {
+        runs: DAYS.iter().map(|_| [None, None]).collect(),
+        selected: 0,
+        message: String::new(),
+        stack: vec![],
+        screen: Screen::Table,
+    };
+    loop {
+        print!("{}", dashboard.draw());
+        std::io::stdout().flush()?;
+        if let Some(key) = read_key()? {
+            if dashboard.key(key) {
+                return Ok(());
+            }
+        }
+    }
+}
@@ -1,3 +1,12 @@
+use std::time::Instant;
+
+use steinerkelvin_aoc_2023::check::gen_day_1_line;
+use steinerkelvin_aoc_2023::cli::{Args, Solver};
+use steinerkelvin_aoc_2023::day_1::{day_1_trebuchet, day_1_trebuchet_bytes};
+use steinerkelvin_aoc_2023::num::Accumulator;
+use steinerkelvin_aoc_2023::rng::Rng;
+use steinerkelvin_aoc_2023::with_accumulator;
+
 const DAY_1_INPUT_A: &str = include_str!("../../input/day_1_a.txt");
 
 /* Day 1: Trebuchet
@@ -20,30 +29,70 @@ Consider your entire calibration document. What is the sum of all of the
 calibration values?
 */
 
-fn day_1_trebuchet() -> Result<u32, Box<dyn std::error::Error>> {
-    let body = DAY_1_INPUT_A;
-    let result = body.lines().map(|line| {
-        let mut chars = line.chars();
-        let d1 = loop {
-            let c = chars.next().expect("There are no digits");
-            if let Some(d1) = c.to_digit(10) {
-                break d1;
+// Implementation selection and benchmark
+
+type Impl<A> = fn(&str) -> Result<A, Box<dyn std::error::Error>>;
+
+fn select_impl<A: Accumulator>(name: &str) -> Result<Impl<A>, Box<dyn std::error::Error>> {
+    match name {
+        "chars" => Ok(day_1_trebuchet),
+        "bytes" => Ok(day_1_trebuchet_bytes),
+        _ => Err(format!("Unknown implementation {:?}, expected chars or bytes", name).into()),
+    }
+}
+
+/// Times both implementations on `lines` generated lines that have a digit.
+fn bench(lines: usize) -> Result<(), Box<dyn std::error::Error>> {
+    let mut rng = Rng::new(1);
+    let mut body = String::new();
+    for _ in 0..lines {
+        let line = loop {
+            let line = gen_day_1_line(&mut rng);
+            if line.bytes().any(|b| b.is_ascii_digit()) {
+                break line;
             }
         };
-        let mut chars = line.chars();
-        loop {
-            let c = chars.next_back().expect("There are no digits");
-            if let Some(d2) = c.to_digit(10) {
-                return 10 * d1 + d2;
-            }
-        }
-    });
-    let result = result.sum::<u32>();
-    Ok(result)
+        body += &line;
+        body.push('\n');
+    }
+    println!("Benchmarking on {} lines ({} bytes)", lines, body.len());
+    let mut results = Vec::new();
+    for name in ["chars", "bytes"] {
+        let solver = select_impl::<u64>(name)?;
+        let start = Instant::now();
+        let result = solver(&body)?;
+        let elapsed = start.elapsed();
+        println!("{:>5}: {} in {:?}", name, result, elapsed);
+        results.push(result);
+    }
+    if results.windows(2).any(|w| w[0] != w[1]) {
+        return Err("Implementations disagree".into());
+    }
+    Ok(())
+}
+
+fn solve<A: Accumulator>(name: &str, body: &str) -> Result<A, Box<dyn std::error::Error>> {
+    select_impl::<A>(name)?(body)
 }
 
+const USAGE: &str =
+    "usage: day_1_a [--impl chars|bytes] [--int u32|u64|u128|big] [--input PATH] [--bench LINES]";
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let result = day_1_trebuchet()?;
+    let mut args = Args::new(USAGE);
+    let mut solver = Solver::new(DAY_1_INPUT_A);
+    let mut name = "chars".to_string();
+    while let Some(flag) = args.next_flag() {
+        if solver.flag(&flag, &mut args)? {
+            continue;
+        }
+        match flag.as_str() {
+            "--impl" => name = args.value(&flag)?,
+            "--bench" => return bench(args.parse(&flag)?),
+            _ => return Err(args.unknown(&flag).into()),
+        }
+    }
+    let result = with_accumulator!(solver.int.as_str(), solve(&name, &solver.body))?;
     println!("Day 1 part 1 result: {}", result);
     Ok(())
 }
@@ -1,4 +1,4 @@
-/** Day 1: Trebuchet, part 2
+/* Day 1: Trebuchet, part 2
 
 Your calculation isn't quite right. It looks like some of the digits are
 actually spelled out with letters: one, two, three, four, five, six, seven,
@@ -20,66 +20,24 @@ Adding these together produces 281.
 What is the sum of all of the calibration values?
 */
 
-const DAY_1_INPUT_B: &str = include_str!("../../input/day_1_b.txt");
-
-const NUMBERS: &[(&str, u32)] = &[
-    ("0", 0),
-    ("1", 1),
-    ("2", 2),
-    ("3", 3),
-    ("4", 4),
-    ("5", 5),
-    ("6", 6),
-    ("7", 7),
-    ("8", 8),
-    ("9", 9),
-    ("zero", 0),
-    ("one", 1),
-    ("two", 2),
-    ("three", 3),
-    ("four", 4),
-    ("five", 5),
-    ("six", 6),
-    ("seven", 7),
-    ("eight", 8),
-    ("nine", 9),
-];
+use steinerkelvin_aoc_2023::cli::{Args, Solver};
+use steinerkelvin_aoc_2023::day_1::day_1_trebuchet_part_2;
+use steinerkelvin_aoc_2023::with_accumulator;
 
-fn prefix_to_digit(prefix: &str) -> Option<u32> {
-    NUMBERS.iter().find_map(|(word, digit)| {
-        if prefix.starts_with(word) {
-            Some(*digit)
-        } else {
-            None
-        }
-    })
-}
+const DAY_1_INPUT_B: &str = include_str!("../../input/day_1_b.txt");
 
-fn day_1_trebuchet_part_2() -> u32 {
-    let body = DAY_1_INPUT_B;
+const USAGE: &str = "usage: day_1_b [--int u32|u64|u128|big] [--input PATH]";
 
-    let result = body.lines().map(|line| {
-        let mut first: Option<u32> = None;
-        let mut last: Option<u32> = None;
-        let mut chars = line.chars();
-        loop {
-            let cur_str = chars.as_str();
-            if let Some(n) = prefix_to_digit(cur_str) {
-                first = first.or(Some(n));
-                last = Some(n);
-            }
-            if chars.next().is_none() {
-                break;
-            }
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut args = Args::new(USAGE);
+    let mut solver = Solver::new(DAY_1_INPUT_B);
+    while let Some(flag) = args.next_flag() {
+        if !solver.flag(&flag, &mut args)? {
+            return Err(args.unknown(&flag).into());
         }
-        let first = first.expect("There are no digits");
-        let last = last.expect("There are no digits");
-        10 * first + last
-    });
-    result.sum()
-}
+    }
 
-fn main() {
-    let result = day_1_trebuchet_part_2();
+    let result = with_accumulator!(solver.int.as_str(), day_1_trebuchet_part_2(&solver.body))?;
     println!("Day 1 part 2 result: {}", result);
+    Ok(())
 }
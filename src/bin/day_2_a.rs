@@ -34,228 +34,69 @@ only 12 red cubes, 13 green cubes, and 14 blue cubes. What is the sum of the IDs
 of those games?
 */
 
-// Parser combinators
-
-#[derive(Clone, Copy)]
-struct Parser<'a> {
-    body: &'a str,
-    pos: usize,
-}
-
-impl Parser<'_> {
-    fn as_str(&self) -> &str {
-        &self.body[self.pos..]
-    }
-}
-
-impl<'a> Parser<'a> {
-    fn at(self, pos: usize) -> Parser<'a> {
-        Parser {
-            body: self.body,
-            pos,
+use steinerkelvin_aoc_2023::cli::{day_2_games, Args, Solver};
+use steinerkelvin_aoc_2023::day_2::{bag_from_str, check_game, day_2_cube_conundrum};
+use steinerkelvin_aoc_2023::day_2::{Game, MaxColors, Violation, DEFAULT_BAG};
+use steinerkelvin_aoc_2023::with_accumulator;
+
+fn print_report(games: &[Game], max_colors: &MaxColors) {
+    for game in games {
+        match check_game(game, max_colors) {
+            Ok(()) => println!("Game {}: possible", game.id),
+            Err(Violation {
+                set,
+                color_qty,
+                max,
+            }) => println!(
+                "Game {}: impossible, set {} shows {} {} but the bag holds {}",
+                game.id,
+                set + 1,
+                color_qty.qty,
+                color_qty.color,
+                max
+            ),
         }
     }
-    fn with<T>(self, item: T) -> ParserResult<'a, T> {
-        Some((self, item))
-    }
-}
-
-type ParserResult<'a, T> = Option<(Parser<'a>, T)>;
-
-fn exact<'a>(parser: Parser<'a>, s: &str) -> ParserResult<'a, ()> {
-    let body = parser.body;
-    let pos = parser.pos;
-    let end = pos + s.len();
-    if end > body.len() {
-        return None;
-    }
-    if &body[pos..end] != s {
-        return None;
-    }
-    parser.at(end).with(())
 }
 
-fn skip_whitespace(parser: Parser) -> ParserResult<()> {
-    let body = parser.body;
-    let mut end = parser.pos;
-    while let Some(c) = body[end..].chars().next() {
-        if !c.is_whitespace() {
-            break;
-        }
-        end += 1;
-    }
-    parser.at(end).with(())
-}
+const USAGE: &str = "usage: day_2_a [--bag red=12,green=13,blue=14] [--bag-file PATH] [--report] \
+[--input PATH [--lenient]] [--int u32|u64|u128|big]";
 
-fn digit(parser: Parser) -> ParserResult<char> {
-    let c = parser.as_str();
-    if let Some(c) = c.chars().next() {
-        if c.is_ascii_digit() {
-            return parser.at(parser.pos + 1).with(c);
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut max_colors = bag_from_str(DEFAULT_BAG).expect("default bag is valid");
+    let mut report = false;
+    let mut lenient = false;
+    let mut args = Args::new(USAGE);
+    let mut solver = Solver::new(DAY_2_INPUT_A);
+    while let Some(flag) = args.next_flag() {
+        if solver.flag(&flag, &mut args)? {
+            continue;
         }
+        let spec = match flag.as_str() {
+            "--bag" => args.value(&flag)?,
+            "--bag-file" => std::fs::read_to_string(args.value(&flag)?)?,
+            "--report" => {
+                report = true;
+                continue;
+            }
+            "--lenient" => {
+                lenient = true;
+                continue;
+            }
+            _ => return Err(args.unknown(&flag).into()),
+        };
+        max_colors = bag_from_str(&spec).ok_or_else(|| args.invalid(&flag, spec.trim()))?;
     }
-    None
-}
-
-fn repeat_to_str<T>(parser: Parser, f: fn(Parser) -> ParserResult<T>) -> ParserResult<&str> {
-    let start = parser;
-    let mut cur_parser = parser;
-    while let Some((parser, _)) = f(cur_parser) {
-        cur_parser = parser;
-    }
-    let end = cur_parser.pos;
-    cur_parser.with(&start.body[start.pos..end])
-}
-
-fn parse_u32(parser: Parser) -> ParserResult<u32> {
-    let (parser, digits) = repeat_to_str(parser, digit)?;
-    let n = digits.parse::<u32>().expect("digits are not a u32");
-    parser.with(n)
-}
-
-fn parse_list<'a, T>(
-    parser: Parser<'a>,
-    sep: &'static str,
-    item_parser: fn(Parser<'a>) -> ParserResult<T>,
-) -> ParserResult<'a, Vec<T>> {
-    let mut nums: Vec<T> = Vec::new();
-    let mut cur_parser = parser;
-    loop {
-        let (parser, num) = item_parser(cur_parser)?;
-        nums.push(num);
-        cur_parser = parser;
-        let (parser, _) = skip_whitespace(cur_parser)?;
-        if let Some((parser, _)) = exact(parser, sep) {
-            let (parser, _) = skip_whitespace(parser)?;
-            cur_parser = parser;
-        } else {
-            break;
-        }
-    }
-    cur_parser.with(nums)
-}
-
-fn consume<T>(parser_result: ParserResult<T>) -> T {
-    let (parser, result) = parser_result.expect("Parser failed");
-    if parser.as_str().is_empty() {
-        result
-    } else {
-        let tail = parser.as_str();
-        panic!("Parser did not consume all input, remaining:\n{:?}", tail)
-    }
-}
-
-// Parsers for Day 2
-
-#[derive(Debug, Clone, Copy)]
-enum Rgb {
-    Red,
-    Green,
-    Blue,
-}
-
-#[derive(Debug, Clone, Copy)]
-struct ColorQty {
-    color: Rgb,
-    qty: u32,
-}
-
-struct Game {
-    id: u32,
-    sets: Vec<Vec<ColorQty>>,
-}
-
-fn parse_games(parser: Parser) -> ParserResult<Vec<Game>> {
-    let mut games = Vec::new();
-    let mut cur_parser = parser;
-    while let Some((parser, game)) = parse_game(cur_parser) {
-        games.push(game);
-        let (parser, _) = skip_whitespace(parser)?;
-        cur_parser = parser;
-    }
-    cur_parser.with(games)
-}
-
-fn parse_game(parser: Parser) -> ParserResult<Game> {
-    let (parser, _) = exact(parser, "Game ")?;
-    let (parser, id) = parse_u32(parser)?;
-    let (parser, _) = exact(parser, ":")?;
-    let (parser, sets) = parse_game_sets(parser)?;
-    parser.with(Game { id, sets })
-}
-
-fn parse_game_sets(parser: Parser) -> ParserResult<Vec<Vec<ColorQty>>> {
-    let (parser, _) = skip_whitespace(parser)?;
-    let (parser, sets) = parse_list(parser, ";", parse_game_set)?;
-    parser.with(sets)
-}
-
-fn parse_game_set(parser: Parser) -> ParserResult<Vec<ColorQty>> {
-    let (parser, _) = skip_whitespace(parser)?;
-    let (parser, game_set) = parse_list(parser, ",", parse_color_qty)?;
-    parser.with(game_set)
-}
-
-fn parse_color_qty(parser: Parser) -> ParserResult<ColorQty> {
-    let (parser, _) = skip_whitespace(parser)?;
-    let (parser, qty) = parse_u32(parser)?;
-    let (parser, _) = skip_whitespace(parser)?;
-    let (parser, color) = parse_rgb(parser)?;
-    parser.with(ColorQty { color, qty })
-}
-
-fn parse_rgb(parser: Parser) -> ParserResult<Rgb> {
-    if let Some((parser, _)) = exact(parser, "red") {
-        parser.with(Rgb::Red)
-    } else if let Some((parser, _)) = exact(parser, "green") {
-        parser.with(Rgb::Green)
-    } else if let Some((parser, _)) = exact(parser, "blue") {
-        parser.with(Rgb::Blue)
-    } else {
-        None
-    }
-}
 
-struct MaxColors {
-    r: u32,
-    g: u32,
-    b: u32,
-}
-impl MaxColors {
-    fn get(&self, color: Rgb) -> u32 {
-        match color {
-            Rgb::Red => self.r,
-            Rgb::Green => self.g,
-            Rgb::Blue => self.b,
-        }
+    let games = day_2_games(&solver.body, lenient)?;
+    if report {
+        print_report(&games, &max_colors);
     }
-}
-
-fn day_2_cube_conundrum() -> u32 {
-    let body = DAY_2_INPUT_A;
-    let max_colors = MaxColors {
-        r: 12,
-        g: 13,
-        b: 14,
-    };
-
-    let games = consume(parse_games(Parser { body, pos: 0 }));
-
-    let process_game = |game: &Game| {
-        for set in &game.sets {
-            for color_qty in set {
-                if color_qty.qty > max_colors.get(color_qty.color) {
-                    return None;
-                }
-            }
-        }
-        Some(game.id)
-    };
-
-    games.iter().filter_map(process_game).sum()
-}
 
-fn main() {
-    let result = day_2_cube_conundrum();
+    let result = with_accumulator!(
+        solver.int.as_str(),
+        day_2_cube_conundrum(&games, &max_colors)
+    )?;
     println!("Day 2 part 1 result: {}", result);
+    Ok(())
 }
@@ -0,0 +1,117 @@
+/* Day 2: Cube Conundrum, allocation benchmark
+
+Counts heap allocations and times the owned parse (`parse_input`, one `Vec`
+per game and set) against the borrowed one (`Games::parse`, flat buffers), and
+the solvers over each. Every allocation goes through a counting global
+allocator.
+
+The borrowed solvers must not allocate per game: they run on the generated
+games and on half of them, and the benchmark fails unless both runs allocate
+the same number of times.
+*/
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+use steinerkelvin_aoc_2023::check::gen_day_2_game;
+use steinerkelvin_aoc_2023::cli::Args;
+use steinerkelvin_aoc_2023::day_2::{bag_from_str, day_2_cube_conundrum, parse_input};
+use steinerkelvin_aoc_2023::day_2::{day_2_borrowed, day_2_borrowed_part_2, Games};
+use steinerkelvin_aoc_2023::day_2::{day_2_cube_conundrum_part_2, DEFAULT_BAG};
+use steinerkelvin_aoc_2023::rng::Rng;
+
+const USAGE: &str = "usage: day_2_alloc [--games N] [--seed N]";
+
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+/// Runs `f`, giving its result with the allocations it made and its time.
+fn measure<T>(name: &str, f: impl FnOnce() -> T) -> (T, usize) {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    let start = Instant::now();
+    let result = f();
+    let elapsed = start.elapsed();
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - before;
+    println!(
+        "{:>22}: {:>9} allocations in {:?}",
+        name, allocations, elapsed
+    );
+    (result, allocations)
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut games: u32 = 100_000;
+    let mut seed: u64 = 0;
+
+    let mut args = Args::new(USAGE);
+    while let Some(flag) = args.next_flag() {
+        match flag.as_str() {
+            "--games" => games = args.parse(&flag)?,
+            "--seed" => seed = args.parse(&flag)?,
+            _ => return Err(args.unknown(&flag).into()),
+        }
+    }
+
+    let mut rng = Rng::new(seed);
+    let body: String = (1..=games)
+        .map(|id| gen_day_2_game(&mut rng, id) + "\n")
+        .collect();
+    let half = &body[..body
+        .match_indices('\n')
+        .nth(games as usize / 2)
+        .map_or(0, |(i, _)| i)];
+    let bag = bag_from_str(DEFAULT_BAG).ok_or("Invalid default bag")?;
+    println!("Benchmarking on {} games ({} bytes)", games, body.len());
+
+    let (owned, _) = measure("owned parse", || parse_input(&body));
+    let owned = owned?;
+    let (owned_1, _) = measure("owned part 1", || day_2_cube_conundrum::<u64>(&owned, &bag));
+    let (owned_2, _) = measure("owned part 2", || {
+        day_2_cube_conundrum_part_2::<u64>(&owned)
+    });
+
+    let (borrowed, _) = measure("borrowed parse", || Games::parse(&body));
+    let borrowed = borrowed?;
+    let (borrowed_1, solve_1) =
+        measure("borrowed part 1", || day_2_borrowed::<u64>(&borrowed, &bag));
+    let (borrowed_2, solve_2) = measure("borrowed part 2", || {
+        day_2_borrowed_part_2::<u64>(&borrowed)
+    });
+
+    let halved = Games::parse(half)?;
+    let (_, half_1) = measure("borrowed part 1 (half)", || {
+        day_2_borrowed::<u64>(&halved, &bag)
+    });
+    let (_, half_2) = measure("borrowed part 2 (half)", || {
+        day_2_borrowed_part_2::<u64>(&halved)
+    });
+
+    if (owned_1?, owned_2?) != (borrowed_1?, borrowed_2?) {
+        return Err("Owned and borrowed solvers disagree".into());
+    }
+    if (solve_1, solve_2) != (half_1, half_2) {
+        return Err("Borrowed solvers allocate per game".into());
+    }
+    Ok(())
+}
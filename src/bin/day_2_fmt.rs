@@ -0,0 +1,62 @@
+/* Day 2: Cube Conundrum, record formatter
+
+Reads game records from a file (or stdin) and prints them back either in the
+canonical `Game N: 3 blue, 4 red; ...` form or as a JSON array. Every printed
+game is parsed again and compared to the original, so normalizing a file never
+changes its meaning.
+
+With `--trace` the parser prints its rule tree to stderr, and a parse error
+also names the deepest rule that failed.
+*/
+
+use std::io::Read;
+
+use steinerkelvin_aoc_2023::day_2::parse_input;
+use steinerkelvin_aoc_2023::parser::{deepest_failure, set_trace, Trace};
+
+const USAGE: &str = "usage: day_2_fmt [--json] [--trace] [PATH]";
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut json = false;
+    let mut path = None;
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--json" => json = true,
+            "--trace" => set_trace(Trace::Tree),
+            _ if path.is_none() && !arg.starts_with("--") => path = Some(arg),
+            _ => return Err(format!("Unknown argument {:?}, {}", arg, USAGE).into()),
+        }
+    }
+
+    let body = match path {
+        Some(path) => std::fs::read_to_string(path)?,
+        None => {
+            let mut body = String::new();
+            std::io::stdin().read_to_string(&mut body)?;
+            body
+        }
+    };
+    let games = parse_input(&body).map_err(|err| match deepest_failure() {
+        Some(failure) => format!("{}; {}", err, failure),
+        None => err.to_string(),
+    })?;
+
+    for game in &games {
+        let printed = game.to_string();
+        let reparsed = parse_input(&printed)
+            .map_err(|err| format!("Game {} does not parse back: {}; {}", game.id, printed, err))?;
+        if reparsed != [game.clone()] {
+            return Err(format!("Game {} does not round-trip: {}", game.id, printed).into());
+        }
+    }
+
+    if json {
+        let games: Vec<String> = games.iter().map(|game| game.to_json()).collect();
+        println!("[{}]", games.join(",\n "));
+    } else {
+        for game in &games {
+            println!("{}", game);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,264 @@
+/* Day 2: Cube Conundrum, random game generator
+
+Emits syntactically valid `Game N: ...` records for fuzzing and load testing.
+Generation is seeded, so the same options always produce the same records, and
+it can be biased so that every game is possible (or impossible) under a bag.
+Draws always show a number of cubes within `--qty`, so a bias that no colour
+can satisfy is rejected.
+
+With `--check ROUNDS` nothing is printed; instead each round generates a fresh
+batch, checks that it keeps to `--qty` and `--bias`, parses its printed form
+back with `parse_games`, and compares `day_2_cube_conundrum` against a
+reference that takes each set's draws out of a full bag in turn. The
+tests in `tests/day_2_gen.rs` run this check on fixed seeds.
+*/
+
+use std::collections::HashMap;
+
+use steinerkelvin_aoc_2023::cli::Args;
+use steinerkelvin_aoc_2023::day_2::{bag_from_str, check_game, day_2_cube_conundrum};
+use steinerkelvin_aoc_2023::day_2::{parse_color, parse_games};
+use steinerkelvin_aoc_2023::day_2::{Color, ColorQty, Game, MaxColors, DEFAULT_BAG};
+use steinerkelvin_aoc_2023::parser::{exact, parse_list, parse_u32, Parser, ParserResult};
+use steinerkelvin_aoc_2023::rng::Rng;
+
+const USAGE: &str = "usage: day_2_gen [--seed N] [--games N] [--sets MIN..MAX] \
+[--colors red,green,blue] [--qty MIN..MAX] [--bag red=12,green=13,blue=14] \
+[--bias possible|impossible] [--check ROUNDS]";
+
+#[derive(Clone, Copy, PartialEq)]
+enum Bias {
+    None,
+    Possible,
+    Impossible,
+}
+
+struct GenConfig {
+    games: u32,
+    sets: (u32, u32),
+    colors: Vec<Color>,
+    qty: (u32, u32),
+    bag: MaxColors,
+    bias: Bias,
+}
+
+/// The colours of `--colors` that the bag can show at least `--qty` MIN of.
+fn fitting(config: &GenConfig) -> Vec<Color> {
+    let fits = |color: &&Color| config.bag.get(**color) >= config.qty.0;
+    config.colors.iter().filter(fits).copied().collect()
+}
+
+/// The colours of `--colors` that can be drawn, within `--qty`, more often
+/// than the bag holds.
+fn exceeding(config: &GenConfig) -> Vec<Color> {
+    let exceeds = |color: &&Color| config.bag.get(**color) < config.qty.1;
+    config.colors.iter().filter(exceeds).copied().collect()
+}
+
+/// Generates a game whose draws all lie within `--qty`. Under a bias, draws
+/// only use colours that fit the bag, and never more cubes than it holds; an
+/// impossible game then gets one draw over the bag's limit. If no colour fits,
+/// any draw makes a game impossible.
+fn gen_game(rng: &mut Rng, config: &GenConfig, id: u32) -> Game {
+    let fitting = fitting(config);
+    let bounded = config.bias != Bias::None && !fitting.is_empty();
+    let choices = if bounded {
+        fitting
+    } else {
+        config.colors.clone()
+    };
+
+    let num_sets = rng.range(config.sets.0, config.sets.1);
+    let mut sets = Vec::new();
+    for _ in 0..num_sets {
+        let mut colors = choices.clone();
+        rng.shuffle(&mut colors);
+        let count = rng.range(1, colors.len() as u32) as usize;
+        let set = colors[..count]
+            .iter()
+            .map(|&color| {
+                let (lo, mut hi) = config.qty;
+                if bounded {
+                    hi = hi.min(config.bag.get(color));
+                }
+                ColorQty {
+                    color,
+                    qty: rng.range(lo, hi),
+                }
+            })
+            .collect();
+        sets.push(set);
+    }
+
+    if config.bias == Bias::Impossible {
+        let exceeding = exceeding(config);
+        let color = exceeding[rng.index(exceeding.len())];
+        let lo = (config.bag.get(color) + 1).max(config.qty.0);
+        let qty = rng.range(lo, config.qty.1);
+        let set_idx = rng.index(sets.len());
+        let set: &mut Vec<ColorQty> = &mut sets[set_idx];
+        match set.iter_mut().find(|color_qty| color_qty.color == color) {
+            Some(color_qty) => color_qty.qty = qty,
+            None => set.push(ColorQty { color, qty }),
+        }
+    }
+
+    Game { id, sets }
+}
+
+fn gen_games(rng: &mut Rng, config: &GenConfig) -> Vec<Game> {
+    (1..=config.games)
+        .map(|id| gen_game(rng, config, id))
+        .collect()
+}
+
+/// Reference for part 1: takes every set's draws out of a full bag, keeping
+/// how many cubes of each colour are left.
+fn reference_part_1(games: &[Game], bag: &MaxColors) -> u64 {
+    let is_possible = |game: &Game| {
+        game.sets.iter().all(|set| {
+            let mut left: HashMap<Color, u32> = HashMap::new();
+            set.iter().all(|color_qty| {
+                let color = color_qty.color;
+                let left = left.entry(color).or_insert_with(|| bag.get(color));
+                match left.checked_sub(color_qty.qty) {
+                    Some(rest) => {
+                        *left = rest;
+                        true
+                    }
+                    None => false,
+                }
+            })
+        })
+    };
+    games
+        .iter()
+        .filter(|game| is_possible(game))
+        .map(|game| game.id as u64)
+        .sum()
+}
+
+fn check(seed: u64, rounds: u64, config: &GenConfig) -> Result<(), Box<dyn std::error::Error>> {
+    for round in 0..rounds {
+        let round_seed = seed.wrapping_add(round);
+        let games = gen_games(&mut Rng::new(round_seed), config);
+        let body: Vec<String> = games.iter().map(|game| game.to_string()).collect();
+        let body = body.join("\n");
+
+        let parsed = match parse_games(Parser {
+            body: &body,
+            pos: 0,
+        }) {
+            Some((parser, parsed)) if parser.as_str().is_empty() => parsed,
+            _ => return Err(format!("Seed {}: generated games do not parse", round_seed).into()),
+        };
+        if parsed != games {
+            return Err(format!("Seed {}: parsed games differ from generated", round_seed).into());
+        }
+        let (lo, hi) = config.qty;
+        let draws = || games.iter().flat_map(|game| game.sets.iter().flatten());
+        if let Some(draw) = draws().find(|draw| draw.qty < lo || draw.qty > hi) {
+            return Err(format!("Seed {}: draw {} is outside --qty", round_seed, draw).into());
+        }
+        let biased = |game: &&Game| match config.bias {
+            Bias::None => true,
+            Bias::Possible => check_game(game, &config.bag).is_ok(),
+            Bias::Impossible => check_game(game, &config.bag).is_err(),
+        };
+        if let Some(game) = games.iter().find(|game| !biased(game)) {
+            return Err(
+                format!("Seed {}: game {} goes against --bias", round_seed, game.id).into(),
+            );
+        }
+
+        let expected = reference_part_1(&games, &config.bag);
+        let result = day_2_cube_conundrum::<u64>(&parsed, &config.bag)?;
+        if result != expected {
+            return Err(format!(
+                "Seed {}: day_2_cube_conundrum gave {}, reference gave {}",
+                round_seed, result, expected
+            )
+            .into());
+        }
+    }
+    println!("{} rounds passed", rounds);
+    Ok(())
+}
+
+// Argument parsing
+
+fn parse_range(parser: Parser) -> ParserResult<(u32, u32)> {
+    let (parser, lo) = parse_u32(parser)?;
+    let (parser, _) = exact(parser, "..")?;
+    let (parser, hi) = parse_u32(parser)?;
+    if lo > hi {
+        return None;
+    }
+    parser.with((lo, hi))
+}
+
+fn parse_colors(parser: Parser) -> ParserResult<Vec<Color>> {
+    parse_list(parser, ",", parse_color)
+}
+
+fn parse_arg<'a, T>(value: &'a str, f: fn(Parser<'a>) -> ParserResult<'a, T>) -> Option<T> {
+    f(Parser {
+        body: value,
+        pos: 0,
+    })
+    .filter(|(parser, _)| parser.as_str().is_empty())
+    .map(|(_, item)| item)
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut seed = 0;
+    let mut rounds = None;
+    let mut config = GenConfig {
+        games: 100,
+        sets: (1, 6),
+        colors: Vec::new(),
+        qty: (1, 20),
+        bag: bag_from_str(DEFAULT_BAG).expect("default bag is valid"),
+        bias: Bias::None,
+    };
+    let mut colors = "red,green,blue".to_string();
+
+    let mut args = Args::new(USAGE);
+    while let Some(flag) = args.next_flag() {
+        match flag.as_str() {
+            "--seed" => seed = args.parse(&flag)?,
+            "--games" => config.games = args.parse(&flag)?,
+            "--colors" => colors = args.value(&flag)?,
+            "--sets" => config.sets = args.parse_with(&flag, |v| parse_arg(v, parse_range))?,
+            "--qty" => config.qty = args.parse_with(&flag, |v| parse_arg(v, parse_range))?,
+            "--bag" => config.bag = args.parse_with(&flag, bag_from_str)?,
+            "--bias" => {
+                config.bias = args.parse_with(&flag, |value| match value {
+                    "possible" => Some(Bias::Possible),
+                    "impossible" => Some(Bias::Impossible),
+                    _ => None,
+                })?
+            }
+            "--check" => rounds = Some(args.parse(&flag)?),
+            _ => return Err(args.unknown(&flag).into()),
+        }
+    }
+    config.colors = parse_arg(&colors, parse_colors).ok_or("Invalid --colors")?;
+    if config.sets.0 == 0 {
+        return Err("Games need at least one set".into());
+    }
+    if config.bias == Bias::Possible && fitting(&config).is_empty() {
+        return Err("No colour fits the bag with at least --qty MIN cubes".into());
+    }
+    if config.bias == Bias::Impossible && exceeding(&config).is_empty() {
+        return Err("Every colour fits the bag with --qty MAX cubes".into());
+    }
+
+    if let Some(rounds) = rounds {
+        return check(seed, rounds, &config);
+    }
+    for game in gen_games(&mut Rng::new(seed), &config) {
+        println!("{}", game);
+    }
+    Ok(())
+}
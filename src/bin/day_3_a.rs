@@ -1,90 +1,68 @@
-struct Matrix<T> {
-    pub width: usize,
-    pub height: usize,
-    pub data: Vec<T>,
-}
+use std::time::Instant;
 
-impl<T: Clone> Matrix<T> {
-    fn new(width: usize, height: usize, fill: T) -> Self {
-        Self {
-            width,
-            height,
-            data: vec![fill; width * height],
-        }
-    }
-}
+use steinerkelvin_aoc_2023::check::gen_schematic;
+use steinerkelvin_aoc_2023::cli::{Args, Solver};
+use steinerkelvin_aoc_2023::day_3::{day_3_gear_ratios, day_3_schematic};
+use steinerkelvin_aoc_2023::day_3::{GearRule, RaggedRows, Schematic};
+use steinerkelvin_aoc_2023::rng::Rng;
+use steinerkelvin_aoc_2023::with_accumulator;
 
-fn entry<T: Clone>(matrix: &mut Matrix<T>, x: i32, y: i32) -> Option<&mut T> {
-    if x < 0 || y < 0 {
-        return None;
-    }
-    let x = x as usize;
-    let y = y as usize;
-    if x >= matrix.width || y >= matrix.height {
-        return None;
-    }
-    Some(&mut matrix.data[x + y * matrix.width])
-}
+const USAGE: &str = "usage: day_3_a [--int u32|u64|u128|big] [--input PATH] [--threads N] \
+[--bench ROWS]";
 
-fn day_3_gear_ratios(input: &str) -> u32 {
-    let lines = input.lines();
-    let lines: Vec<&[u8]> = lines.map(|line| line.as_bytes()).collect();
+fn bench(rows: usize, threads: usize) -> Result<(), Box<dyn std::error::Error>> {
+    let input = gen_schematic(&mut Rng::new(3), 140, rows);
+    println!("Benchmarking on {} rows ({} bytes)", rows, input.len());
 
-    let width = lines[0].len();
-    let height = lines.len();
-    let mut flag_matrix = Matrix::<bool>::new(width, height, false);
+    let start = Instant::now();
+    let sequential = Schematic::parse(&input)?;
+    println!("  sequential: {:?}", start.elapsed());
 
-    for (y, line) in lines.iter().enumerate() {
-        for (x, &byte) in line.iter().enumerate() {
-            if !byte.is_ascii_digit() && byte != b'.' {
-                // Mark adjacent cells
-                for dx in -1..=1 {
-                    for dy in -1..=1 {
-                        if let Some(entry) = entry(&mut flag_matrix, x as i32 + dx, y as i32 + dy) {
-                            *entry = true;
-                        }
-                    }
-                }
-            }
-        }
-    }
+    let start = Instant::now();
+    let parallel = Schematic::parse_parallel(&input, RaggedRows::Reject, threads)?;
+    println!("{:>3} threads: {:?}", threads, start.elapsed());
 
-    let mut is_symbol_adjacent = |x: i32, y: i32| {
-        if let Some(true) = entry(&mut flag_matrix, x, y) {
-            return true;
-        }
-        false
-    };
+    if parallel != sequential {
+        return Err("Parallel and sequential schematics differ".into());
+    }
+    let part_1: u64 = parallel.part_numbers().map(|number| number.value).sum();
+    let part_2: u128 = parallel
+        .gears::<u128>(&GearRule::puzzle())?
+        .iter()
+        .map(|(_, ratio)| ratio)
+        .sum();
+    println!("Part 1: {}, part 2: {}", part_1, part_2);
+    Ok(())
+}
 
-    let mut sum = 0;
-    for (y, line) in lines.iter().enumerate() {
-        let mut current_number: Option<u32> = None;
-        let mut is_part = false;
-        for (x, &byte) in line.iter().enumerate() {
-            if byte.is_ascii_digit() {
-                let d = (byte - b'0') as u32;
-                current_number = current_number.map(|n| n * 10 + d).or(Some(d));
-                if !is_part && is_symbol_adjacent(x as i32, y as i32) {
-                    is_part = true;
-                }
-            } else {
-                if let (Some(number), true) = (current_number, is_part) {
-                    sum += number;
-                }
-                current_number = None;
-                is_part = false;
-            }
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut args = Args::new(USAGE);
+    let mut solver = Solver::new(include_str!("../../input/day_3_a.txt"));
+    let mut bench_rows = None;
+    let mut threads = None;
+    while let Some(flag) = args.next_flag() {
+        if solver.flag(&flag, &mut args)? {
+            continue;
         }
-        if let (Some(number), true) = (current_number, is_part) {
-            sum += number;
+        match flag.as_str() {
+            "--bench" => bench_rows = Some(args.parse(&flag)?),
+            "--threads" => threads = Some(args.parse(&flag)?),
+            _ => return Err(args.unknown(&flag).into()),
         }
     }
+    if let Some(rows) = bench_rows {
+        let available = std::thread::available_parallelism().map_or(1, |n| n.get());
+        return bench(rows, threads.unwrap_or(available));
+    }
 
-    sum
-}
-
-fn main() {
-    let input = include_str!("../../input/day_3_a.txt");
-    let result = day_3_gear_ratios(input);
+    let (int, input) = (solver.int.as_str(), solver.body.as_str());
+    let result = match threads {
+        Some(threads) => {
+            let schematic = Schematic::parse_parallel(input, RaggedRows::Reject, threads)?;
+            with_accumulator!(int, day_3_schematic(&schematic))?
+        }
+        None => with_accumulator!(int, day_3_gear_ratios(input))?,
+    };
     println!("Day 3 part 1 result: {}", result);
+    Ok(())
 }
@@ -1,4 +1,4 @@
-/** Day 3: Gear Ratios (Part 2)
+/* Day 3: Gear Ratios (Part 2)
 
 The engineer finds the missing part and installs it in the engine! As the engine springs to life, you jump in the closest gondola, finally ready to ascend to the water source.
 
@@ -28,100 +28,37 @@ What is the sum of all of the gear ratios in your engine schematic?
 
 */
 
-struct Matrix<T> {
-    pub width: usize,
-    pub height: usize,
-    pub data: Vec<T>,
-}
-
-impl<T: Clone> Matrix<T> {
-    fn new(width: usize, height: usize, fill: T) -> Self {
-        Self {
-            width,
-            height,
-            data: vec![fill; width * height],
+use steinerkelvin_aoc_2023::cli::{gear_rule_flag, Args, Solver};
+use steinerkelvin_aoc_2023::day_3::{day_3_gear_ratios_part_2, day_3_schematic_part_2};
+use steinerkelvin_aoc_2023::day_3::{GearRule, RaggedRows, Schematic};
+use steinerkelvin_aoc_2023::with_accumulator;
+
+const USAGE: &str = "usage: day_3_b [--symbols '*'] [--arity N | --min-arity N] \
+[--reduce product|sum|max] [--int u32|u64|u128|big] [--input PATH] [--threads N]";
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut args = Args::new(USAGE);
+    let mut solver = Solver::new(include_str!("../../input/day_3_b.txt"));
+    let mut rule = GearRule::puzzle();
+    let mut threads = None;
+    while let Some(flag) = args.next_flag() {
+        if solver.flag(&flag, &mut args)? || gear_rule_flag(&mut rule, &flag, &mut args)? {
+            continue;
         }
-    }
-}
-
-fn entry<T: Clone>(matrix: &Matrix<T>, x: i32, y: i32) -> Option<&T> {
-    if x < 0 || y < 0 {
-        return None;
-    }
-    let x = x as usize;
-    let y = y as usize;
-    if x >= matrix.width || y >= matrix.height {
-        return None;
-    }
-    Some(&matrix.data[x + y * matrix.width])
-}
-
-fn day_3_gear_ratios_part_2(input: &str) -> u32 {
-    let lines = input.lines();
-    let lines: Vec<&[u8]> = lines.map(|line| line.as_bytes()).collect();
-
-    let width = lines[0].len();
-    let height = lines.len();
-
-    type Gear = Vec<u32>;
-    let mut gear_matrix = Matrix::<Option<Gear>>::new(width, height, None);
-
-    for (y, line) in lines.iter().enumerate() {
-        for (x, &byte) in line.iter().enumerate() {
-            if byte == b'*' {
-                gear_matrix.data[x + y * width] = Some(Gear::new());
-            }
+        match flag.as_str() {
+            "--threads" => threads = Some(args.parse(&flag)?),
+            _ => return Err(args.unknown(&flag).into()),
         }
     }
 
-    for (y, line) in lines.iter().enumerate() {
-        let mut current_number: Option<u32> = None;
-        let mut current_gears = std::collections::HashSet::new();
-        for (x, &byte) in line.iter().enumerate() {
-            if byte.is_ascii_digit() {
-                let d = (byte - b'0') as u32;
-                current_number = current_number.map(|n| n * 10 + d).or(Some(d));
-                for dx in -1..=1 {
-                    for dy in -1..=1 {
-                        let gear_x = x as i32 + dx;
-                        let gear_y = y as i32 + dy;
-                        if let Some(Some(_)) = entry(&gear_matrix, gear_x, gear_y) {
-                            current_gears.insert((gear_x as usize, gear_y as usize));
-                        }
-                    }
-                }
-            } else if let Some(number) = current_number {
-                for &(x, y) in &current_gears {
-                    gear_matrix.data[x + y * width]
-                        .as_mut()
-                        .unwrap()
-                        .push(number);
-                }
-                current_number = None;
-                current_gears.clear();
-            }
-        }
-        if let Some(number) = current_number {
-            for &(x, y) in &current_gears {
-                gear_matrix.data[x + y * width]
-                    .as_mut()
-                    .unwrap()
-                    .push(number);
-            }
+    let (int, input) = (solver.int.as_str(), solver.body.as_str());
+    let result = match threads {
+        Some(threads) => {
+            let schematic = Schematic::parse_parallel(input, RaggedRows::Reject, threads)?;
+            with_accumulator!(int, day_3_schematic_part_2(&schematic, &rule))?
         }
-    }
-
-    let mut sum = 0;
-    for gear in gear_matrix.data.iter().flatten() {
-        if gear.len() == 2 {
-            sum += gear[0] * gear[1];
-        }
-    }
-    sum
-}
-
-fn main() {
-    let input = include_str!("../../input/day_3_b.txt");
-    let result = day_3_gear_ratios_part_2(input);
+        None => with_accumulator!(int, day_3_gear_ratios_part_2(input, &rule))?,
+    };
     println!("Day 3 part 2 result: {}", result);
+    Ok(())
 }
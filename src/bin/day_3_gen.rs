@@ -0,0 +1,286 @@
+/* Day 3: Gear Ratios, random schematic generator
+
+Emits an engine schematic of the requested size and density on stdout, and its
+ground-truth part number sum and gear ratio sum on stderr. Generation is
+seeded, so the same options always produce the same schematic.
+
+Besides random numbers and symbols, every schematic gets the awkward cases:
+numbers touching each border, numbers next to several gears, and `*` symbols
+with three neighbouring numbers (which are not gears). The answers come from the
+generator's own list of placed numbers, not from scanning the grid.
+
+With `--check ROUNDS` nothing is printed; instead each round generates a fresh
+schematic and compares both `day_3_gear_ratios` functions, and the parallel
+parser, against the ground truth. The tests in `tests/day_3_gen.rs` run this
+check on fixed seeds.
+*/
+
+use steinerkelvin_aoc_2023::cli::Args;
+use steinerkelvin_aoc_2023::day_3::{day_3_gear_ratios, day_3_gear_ratios_part_2};
+use steinerkelvin_aoc_2023::day_3::{GearRule, RaggedRows, Schematic};
+use steinerkelvin_aoc_2023::rng::Rng;
+
+const USAGE: &str = "usage: day_3_gen [--seed N] [--rows N] [--cols N] [--numbers PERCENT] \
+[--symbols PERCENT] [--check ROUNDS]";
+
+const SYMBOLS: &[u8] = b"*#+$/=%@&-";
+
+/// Small stamps for the edge cases; `n` is a random digit.
+const MOTIFS: &[&[&str]] = &[
+    // A `*` with three neighbours
+    &["nn.nn", "..*..", ".nnn."],
+    // A number between two gears
+    &["n*nn*n"],
+    // Gears above and below a number
+    &["..*..", ".nnn.", "*...n"],
+    // A vertical gear
+    &["n", "*", "n"],
+];
+
+struct GenConfig {
+    rows: usize,
+    cols: usize,
+    numbers: u32,
+    symbols: u32,
+}
+
+/// A number as placed by the generator.
+struct Placed {
+    value: u64,
+    row: usize,
+    col_start: usize,
+    col_end: usize,
+}
+
+struct Generated {
+    grid: Vec<Vec<u8>>,
+    numbers: Vec<Placed>,
+}
+
+impl Generated {
+    fn is_free(&self, row: usize, col_start: usize, col_end: usize) -> bool {
+        let line = &self.grid[row];
+        let left_ok = col_start == 0 || !line[col_start - 1].is_ascii_digit();
+        let right_ok = col_end == line.len() || !line[col_end].is_ascii_digit();
+        left_ok && right_ok && line[col_start..col_end].iter().all(|&b| b == b'.')
+    }
+
+    fn place_number(&mut self, rng: &mut Rng, row: usize, col_start: usize, len: usize) -> bool {
+        let col_end = col_start + len;
+        if col_end > self.grid[row].len() || !self.is_free(row, col_start, col_end) {
+            return false;
+        }
+        let mut value = 0;
+        for col in col_start..col_end {
+            let digit = if col == col_start {
+                rng.range(1, 9)
+            } else {
+                rng.range(0, 9)
+            };
+            self.grid[row][col] = b'0' + digit as u8;
+            value = value * 10 + digit as u64;
+        }
+        self.numbers.push(Placed {
+            value,
+            row,
+            col_start,
+            col_end,
+        });
+        true
+    }
+
+    fn place_motif(&mut self, rng: &mut Rng, motif: &[&str], row: usize, col: usize) -> bool {
+        let width = motif[0].len();
+        if row + motif.len() > self.grid.len() || col + width > self.grid[0].len() {
+            return false;
+        }
+        if !(0..motif.len()).all(|dy| self.is_free(row + dy, col, col + width)) {
+            return false;
+        }
+        for (dy, line) in motif.iter().enumerate() {
+            let mut x = 0;
+            while x < width {
+                let run = line[x..].bytes().take_while(|&b| b == b'n').count();
+                if run > 0 {
+                    self.place_number(rng, row + dy, col + x, run);
+                    x += run;
+                } else {
+                    self.grid[row + dy][col + x] = line.as_bytes()[x];
+                    x += 1;
+                }
+            }
+        }
+        true
+    }
+
+    fn to_text(&self) -> String {
+        let lines: Vec<&str> = self
+            .grid
+            .iter()
+            .map(|line| std::str::from_utf8(line).unwrap())
+            .collect();
+        lines.join("\n") + "\n"
+    }
+
+    /// Ground truth from the placed numbers: a number is a part when a symbol
+    /// lies in the box around it, and a gear is a `*` inside exactly two boxes.
+    fn answers(&self) -> (u128, u128) {
+        let around = |number: &Placed, row: usize, col: usize| {
+            row + 1 >= number.row
+                && row <= number.row + 1
+                && col + 1 >= number.col_start
+                && col <= number.col_end
+        };
+        let mut symbols = Vec::new();
+        for (row, line) in self.grid.iter().enumerate() {
+            for (col, &byte) in line.iter().enumerate() {
+                if byte != b'.' && !byte.is_ascii_digit() {
+                    symbols.push((row, col, byte));
+                }
+            }
+        }
+
+        let part_sum = self
+            .numbers
+            .iter()
+            .filter(|number| {
+                symbols
+                    .iter()
+                    .any(|&(row, col, _)| around(number, row, col))
+            })
+            .map(|number| number.value as u128)
+            .sum();
+        let gear_sum = symbols
+            .iter()
+            .filter(|&&(_, _, byte)| byte == b'*')
+            .map(|&(row, col, _)| {
+                let neighbours: Vec<u128> = self
+                    .numbers
+                    .iter()
+                    .filter(|number| around(number, row, col))
+                    .map(|number| number.value as u128)
+                    .collect();
+                match neighbours.as_slice() {
+                    [a, b] => a * b,
+                    _ => 0,
+                }
+            })
+            .sum();
+        (part_sum, gear_sum)
+    }
+}
+
+fn generate(rng: &mut Rng, config: &GenConfig) -> Generated {
+    let mut generated = Generated {
+        grid: vec![vec![b'.'; config.cols]; config.rows],
+        numbers: Vec::new(),
+    };
+    let rows = config.rows;
+    let cols = config.cols;
+
+    // Edge cases first, so that random filling cannot crowd them out
+    for motif in MOTIFS {
+        for _ in 0..(rows * cols / 400).max(1) {
+            let row = rng.index(rows);
+            let col = rng.index(cols);
+            generated.place_motif(rng, motif, row, col);
+        }
+    }
+    for _ in 0..(rows / 4).max(1) {
+        let len = rng.range(1, 3) as usize;
+        let row = rng.index(rows);
+        generated.place_number(rng, row, 0, len);
+        generated.place_number(rng, row, cols.saturating_sub(len), len);
+        let col = rng.index(cols);
+        generated.place_number(rng, 0, col, len);
+        generated.place_number(rng, rows - 1, col, len);
+    }
+
+    for row in 0..rows {
+        for col in 0..cols {
+            if generated.grid[row][col] != b'.' {
+                continue;
+            }
+            let roll = rng.range(0, 99);
+            if roll < config.symbols {
+                generated.grid[row][col] = SYMBOLS[rng.index(SYMBOLS.len())];
+            } else if roll < config.symbols + config.numbers {
+                let len = rng.range(1, 3) as usize;
+                generated.place_number(rng, row, col, len);
+            }
+        }
+    }
+    generated
+}
+
+fn check(seed: u64, rounds: u64, config: &GenConfig) -> Result<(), Box<dyn std::error::Error>> {
+    for round in 0..rounds {
+        let round_seed = seed.wrapping_add(round);
+        let generated = generate(&mut Rng::new(round_seed), config);
+        let input = generated.to_text();
+        let (part_sum, gear_sum) = generated.answers();
+
+        let result = day_3_gear_ratios::<u128>(&input)?;
+        if result != part_sum {
+            return Err(format!(
+                "Seed {}: day_3_gear_ratios gave {}, expected {}",
+                round_seed, result, part_sum
+            )
+            .into());
+        }
+        let result = day_3_gear_ratios_part_2::<u128>(&input, &GearRule::puzzle())?;
+        if result != gear_sum {
+            return Err(format!(
+                "Seed {}: day_3_gear_ratios_part_2 gave {}, expected {}",
+                round_seed, result, gear_sum
+            )
+            .into());
+        }
+        let parallel = Schematic::parse_parallel(&input, RaggedRows::Reject, 4)?;
+        if parallel != Schematic::parse(&input)? {
+            return Err(format!("Seed {}: parallel parse differs", round_seed).into());
+        }
+    }
+    println!("{} rounds passed", rounds);
+    Ok(())
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut seed = 0;
+    let mut rounds = None;
+    let mut config = GenConfig {
+        rows: 140,
+        cols: 140,
+        numbers: 10,
+        symbols: 4,
+    };
+
+    let mut args = Args::new(USAGE);
+    while let Some(flag) = args.next_flag() {
+        match flag.as_str() {
+            "--seed" => seed = args.parse(&flag)?,
+            "--rows" => config.rows = args.parse(&flag)?,
+            "--cols" => config.cols = args.parse(&flag)?,
+            "--numbers" => config.numbers = args.parse(&flag)?,
+            "--symbols" => config.symbols = args.parse(&flag)?,
+            "--check" => rounds = Some(args.parse(&flag)?),
+            _ => return Err(args.unknown(&flag).into()),
+        }
+    }
+    if config.rows == 0 || config.cols == 0 {
+        return Err("Schematics need at least one row and column".into());
+    }
+    if config.numbers + config.symbols > 100 {
+        return Err("--numbers and --symbols add up to more than 100%".into());
+    }
+
+    if let Some(rounds) = rounds {
+        return check(seed, rounds, &config);
+    }
+    let generated = generate(&mut Rng::new(seed), &config);
+    let (part_sum, gear_sum) = generated.answers();
+    print!("{}", generated.to_text());
+    eprintln!("Part 1: {}", part_sum);
+    eprintln!("Part 2: {}", gear_sum);
+    Ok(())
+}
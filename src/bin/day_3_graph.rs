@@ -0,0 +1,29 @@
+/* Day 3: Gear Ratios, adjacency graph export
+
+Prints the bipartite graph between numbers and the symbols next to them, as
+Graphviz DOT or as a JSON node/edge list, for analysis in other tools.
+*/
+
+use steinerkelvin_aoc_2023::day_3::{Schematic, EXAMPLE};
+
+const USAGE: &str = "usage: day_3_graph [--example] [--json]";
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut input = include_str!("../../input/day_3_a.txt");
+    let mut json = false;
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--example" => input = EXAMPLE,
+            "--json" => json = true,
+            _ => return Err(format!("Unknown argument {:?}, {}", arg, USAGE).into()),
+        }
+    }
+
+    let schematic = Schematic::parse(input)?;
+    if json {
+        println!("{}", schematic.to_json());
+    } else {
+        print!("{}", schematic.to_dot());
+    }
+    Ok(())
+}
@@ -0,0 +1,57 @@
+/* Day 3: Gear Ratios, schematic viewer
+
+Renders the engine schematic for debugging wrong answers. On a terminal, part
+numbers are green, numbers that are not parts are red, gears are yellow, and the
+cells next to a symbol have a shaded background. When stdout is not a terminal
+(or with `--plain`) each row is followed by a marker row instead:
+
+  ^  digit of a part number
+  x  digit of a number that is not a part
+  G  gear
+  :  other cell next to a symbol
+
+The gears and their ratios are listed after the schematic. Gears follow the
+puzzle's rule unless `--symbols`, `--arity`, `--min-arity` or `--reduce` change
+it, as for `day_3_b`.
+*/
+
+use std::io::IsTerminal;
+
+use steinerkelvin_aoc_2023::cli::{gear_rule_flag, Args};
+use steinerkelvin_aoc_2023::day_3::{GearRule, Schematic, EXAMPLE, RESET, YELLOW};
+use steinerkelvin_aoc_2023::num::BigUint;
+
+const USAGE: &str = "usage: day_3_view [--example] [--color | --plain] [--symbols '*'] \
+[--arity N | --min-arity N] [--reduce product|sum|max]";
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut input = include_str!("../../input/day_3_a.txt");
+    let mut color = std::io::stdout().is_terminal();
+    let mut rule = GearRule::puzzle();
+    let mut args = Args::new(USAGE);
+    while let Some(flag) = args.next_flag() {
+        if gear_rule_flag(&mut rule, &flag, &mut args)? {
+            continue;
+        }
+        match flag.as_str() {
+            "--example" => input = EXAMPLE,
+            "--color" => color = true,
+            "--plain" => color = false,
+            _ => return Err(args.unknown(&flag).into()),
+        }
+    }
+
+    let schematic = Schematic::parse(input)?;
+    print!("{}", schematic.render(input, &rule, color));
+
+    println!();
+    for (symbol, ratio) in schematic.gears::<BigUint>(&rule)? {
+        let ratio = if color {
+            format!("{}{}{}", YELLOW, ratio, RESET)
+        } else {
+            ratio.to_string()
+        };
+        println!("Gear at ({}, {}): ratio {}", symbol.col, symbol.row, ratio);
+    }
+    Ok(())
+}
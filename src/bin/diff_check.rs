@@ -0,0 +1,41 @@
+/* Differential check of every solver against its naive reference
+
+Each round generates a small random input per puzzle part and runs both the
+real solver and the one from `reference` on it, as `check::diff` does for the
+tests in `tests/diff_check.rs`, but for as many rounds as asked for. The first
+disagreement is shrunk and printed with both answers.
+*/
+
+use steinerkelvin_aoc_2023::check::diff::{disagreement, CHECKS};
+use steinerkelvin_aoc_2023::cli::Args;
+
+const USAGE: &str = "usage: diff_check [--seed N] [--rounds N]";
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut seed: u64 = 0;
+    let mut rounds: u64 = 2_000;
+
+    let mut args = Args::new(USAGE);
+    while let Some(flag) = args.next_flag() {
+        match flag.as_str() {
+            "--seed" => seed = args.parse(&flag)?,
+            "--rounds" => rounds = args.parse(&flag)?,
+            _ => return Err(args.unknown(&flag).into()),
+        }
+    }
+
+    for check in CHECKS {
+        if let Some((round_seed, input)) = disagreement(check, seed, rounds) {
+            println!(
+                "{}: disagreement with seed {}, minimized to:",
+                check.name, round_seed
+            );
+            println!("{}", input.trim_end_matches('\n'));
+            println!("  solver: {:?}", (check.solver)(&input));
+            println!("  reference: {:?}", (check.reference)(&input));
+            return Err(format!("{} disagrees with its reference", check.name).into());
+        }
+        println!("{}: {} rounds agree", check.name, rounds);
+    }
+    Ok(())
+}
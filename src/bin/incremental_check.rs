@@ -0,0 +1,61 @@
+/* Check of the incremental answers against full recomputation
+
+Runs the checks of `check::edits`, as the tests in `tests/incremental_check.rs`
+do, but with as many rounds and edits per round as asked for.
+
+At the end of each it reports how many records (lines, games) an edit
+recomputed on average, against the average number of records in a document.
+*/
+
+use steinerkelvin_aoc_2023::check::edits::{self, Check, Options, Summary};
+use steinerkelvin_aoc_2023::check::edits::{DAY_1_PART_1, DAY_1_PART_2, DAY_2_PART_1};
+use steinerkelvin_aoc_2023::check::edits::{DAY_2_PART_2, DAY_3_PART_1, DAY_3_PART_2};
+use steinerkelvin_aoc_2023::cli::Args;
+use steinerkelvin_aoc_2023::incremental::Document;
+
+const USAGE: &str = "usage: incremental_check [--seed N] [--rounds N] [--edits N]";
+
+/// Runs `check`, and prints how many records an edit recomputed on average.
+fn report<D: Document>(check: &Check<D>, options: &Options) -> Result<(), String> {
+    let Summary {
+        edits,
+        answered,
+        recomputed,
+        records,
+    } = edits::check(check, options)?;
+    println!(
+        "{}: {} edits agree, {} with an answer, {:.1} of {:.1} records recomputed per edit",
+        check.name,
+        edits,
+        answered,
+        recomputed as f64 / edits.max(1) as f64,
+        records as f64 / edits.max(1) as f64
+    );
+    Ok(())
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut options = Options {
+        seed: 0,
+        rounds: 500,
+        edits: 20,
+    };
+
+    let mut args = Args::new(USAGE);
+    while let Some(flag) = args.next_flag() {
+        match flag.as_str() {
+            "--seed" => options.seed = args.parse(&flag)?,
+            "--rounds" => options.rounds = args.parse(&flag)?,
+            "--edits" => options.edits = args.parse(&flag)?,
+            _ => return Err(args.unknown(&flag).into()),
+        }
+    }
+
+    report(&DAY_1_PART_1, &options)?;
+    report(&DAY_1_PART_2, &options)?;
+    report(&DAY_2_PART_1, &options)?;
+    report(&DAY_2_PART_2, &options)?;
+    report(&DAY_3_PART_1, &options)?;
+    report(&DAY_3_PART_2, &options)?;
+    Ok(())
+}
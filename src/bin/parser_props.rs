@@ -0,0 +1,44 @@
+/* Property checks for the parser combinators
+
+Runs every property of `check::props` on as many generated cases as asked for,
+by default many more than the tests in `tests/parser_props.rs` do. A failing
+case is reported with its seed, so `--seed` reproduces it.
+*/
+
+use std::panic;
+
+use steinerkelvin_aoc_2023::check::props::{failure, PROPERTIES};
+use steinerkelvin_aoc_2023::cli::Args;
+
+const USAGE: &str = "usage: parser_props [--seed N] [--cases N]";
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut seed: u64 = 0;
+    let mut cases: u64 = 10_000;
+
+    let mut args = Args::new(USAGE);
+    while let Some(flag) = args.next_flag() {
+        match flag.as_str() {
+            "--seed" => seed = args.parse(&flag)?,
+            "--cases" => cases = args.parse(&flag)?,
+            _ => return Err(args.unknown(&flag).into()),
+        }
+    }
+
+    // Panics are expected from `consume`, and reported as failures otherwise
+    panic::set_hook(Box::new(|_| {}));
+    let mut failed = false;
+    for (name, property) in PROPERTIES {
+        match failure(*property, seed, cases) {
+            None => println!("{}: {} cases passed", name, cases),
+            Some((case_seed, message)) => {
+                println!("{}: failed with seed {}: {}", name, case_seed, message);
+                failed = true;
+            }
+        }
+    }
+    if failed {
+        return Err("Some properties failed".into());
+    }
+    Ok(())
+}
@@ -0,0 +1,337 @@
+/* Interactive queries over a day's parsed input
+
+Loads one day's input through its parser and answers queries typed one per
+line, for debugging wrong answers without writing throwaway code:
+
+  day 1  line N                 the line, with every digit and word matched
+                                on it and its value in both parts
+  day 2  game ID                the game, its minimal bag, and whether the
+                                puzzle bag allows it
+         games where COLOR OP N [and COLOR OP N ...]
+                                games whose largest draw of COLOR compares to
+                                N by OP, one of < <= = >= >
+  day 3  parts near (X,Y)       numbers next to the cell at column X, row Y,
+                                both counted from 0
+         gear at (X,Y)          the symbol at that cell, the numbers next to
+                                it, and its ratio if it is a gear
+  any    answer                 the answers to both parts
+         help, quit
+
+Queries are read until the end of input, so they can also be piped in. The
+runner starts it as a subcommand too, with
+`cargo run --bin steinerkelvin-aoc-2023 -- repl 3`.
+*/
+
+use std::io::{BufRead, IsTerminal, Write};
+
+use steinerkelvin_aoc_2023::cli::Args;
+use steinerkelvin_aoc_2023::day_1::{day_1_trebuchet, day_1_trebuchet_part_2, NUMBERS};
+use steinerkelvin_aoc_2023::day_2::{bag_from_str, check_game, day_2_cube_conundrum, min_colors};
+use steinerkelvin_aoc_2023::day_2::{day_2_cube_conundrum_part_2, parse_input_recovering};
+use steinerkelvin_aoc_2023::day_2::{Color, Game, DEFAULT_BAG};
+use steinerkelvin_aoc_2023::day_3::{day_3_gear_ratios, day_3_gear_ratios_part_2};
+use steinerkelvin_aoc_2023::day_3::{GearRule, Schematic};
+
+const USAGE: &str = "usage: repl 1|2|3 [--input PATH]";
+
+const INPUTS: [&str; 3] = [
+    include_str!("../../input/day_1_a.txt"),
+    include_str!("../../input/day_2_a.txt"),
+    include_str!("../../input/day_3_a.txt"),
+];
+
+const HELP: [&str; 3] = [
+    "line N, answer, help, quit",
+    "game ID, games where COLOR OP N [and ...], answer, help, quit",
+    "parts near (X,Y), gear at (X,Y), answer, help, quit",
+];
+
+enum Loaded {
+    Day1(String),
+    Day2(Vec<Game>),
+    Day3(Schematic, String),
+}
+
+type Reply = Result<String, String>;
+
+fn parse_number<T: std::str::FromStr>(word: &str) -> Result<T, String> {
+    word.parse()
+        .map_err(|_| format!("Expected a number, found {:?}", word))
+}
+
+/// Reads `(X,Y)` from the rest of a query, spaces allowed.
+fn parse_cell(words: &[&str]) -> Result<(usize, usize), String> {
+    let text: String = words.concat();
+    let inner = text
+        .strip_prefix('(')
+        .and_then(|text| text.strip_suffix(')'))
+        .and_then(|text| text.split_once(','))
+        .ok_or_else(|| format!("Expected a cell like (10,4), found {:?}", text))?;
+    Ok((parse_number(inner.0)?, parse_number(inner.1)?))
+}
+
+// Day 1
+
+fn query_line(body: &str, n: usize) -> Reply {
+    let line = n
+        .checked_sub(1)
+        .and_then(|index| body.lines().nth(index))
+        .ok_or_else(|| format!("There is no line {}", n))?;
+    let mut out = format!("line {}: {}\n", n, line);
+    let mut matches = Vec::new();
+    for (pos, _) in line.char_indices() {
+        for &(word, digit) in NUMBERS {
+            if line[pos..].starts_with(word) {
+                matches.push((pos, word, digit));
+                let kind = if word.len() == 1 { "digit" } else { "word" };
+                out += &format!("  column {}: {} {:?} = {}\n", pos + 1, kind, word, digit);
+            }
+        }
+    }
+    let value = |matches: &[&(usize, &str, u32)]| match (matches.first(), matches.last()) {
+        (Some(first), Some(last)) => format!("{}", first.2 * 10 + last.2),
+        _ => "no digits".to_string(),
+    };
+    let digits: Vec<_> = matches
+        .iter()
+        .filter(|(_, word, _)| word.len() == 1)
+        .collect();
+    let all: Vec<_> = matches.iter().collect();
+    out += &format!("  part 1: {}\n  part 2: {}", value(&digits), value(&all));
+    Ok(out)
+}
+
+fn query_day_1(body: &str, words: &[&str]) -> Reply {
+    match words {
+        ["line", n] => query_line(body, parse_number(n)?),
+        ["answer"] => Ok(format!(
+            "part 1: {}\npart 2: {}",
+            day_1_trebuchet::<u128>(body).map_err(|err| err.to_string())?,
+            day_1_trebuchet_part_2::<u128>(body).map_err(|err| err.to_string())?
+        )),
+        _ => Err(format!("Unknown query, try {}", HELP[0])),
+    }
+}
+
+// Day 2
+
+fn query_game(games: &[Game], id: u32) -> Reply {
+    let bag = bag_from_str(DEFAULT_BAG).ok_or("Invalid default bag")?;
+    let mut out = Vec::new();
+    for game in games.iter().filter(|game| game.id == id) {
+        out.push(game.to_string());
+        let colors = min_colors(game);
+        let drawn: Vec<Color> = Color::all()
+            .filter(|&color| game.sets.iter().flatten().any(|c| c.color == color))
+            .collect();
+        let min_bag: Vec<String> = drawn
+            .iter()
+            .map(|&color| format!("{} {}", colors.get(color), color))
+            .collect();
+        out.push(format!("  minimal bag: {}", min_bag.join(", ")));
+        out.push(match check_game(game, &bag) {
+            Ok(()) => format!("  possible with {}", DEFAULT_BAG),
+            Err(violation) => format!(
+                "  impossible with {}: set {} draws {}, the bag holds {}",
+                DEFAULT_BAG,
+                violation.set + 1,
+                violation.color_qty,
+                violation.max
+            ),
+        });
+    }
+    if out.is_empty() {
+        return Err(format!("There is no game {}", id));
+    }
+    Ok(out.join("\n"))
+}
+
+type Condition<'a> = (&'a str, &'a str, u32);
+
+fn query_games_where(games: &[Game], words: &[&str]) -> Reply {
+    let mut conditions: Vec<Condition> = Vec::new();
+    for clause in words.split(|&word| word == "and") {
+        let &[color, op, n] = clause else {
+            return Err("Expected conditions like red > 12 and blue < 3".to_string());
+        };
+        if !["<", "<=", "=", ">=", ">"].contains(&op) {
+            return Err(format!("Unknown comparison {:?}, use < <= = >= >", op));
+        }
+        conditions.push((color, op, parse_number(n)?));
+    }
+    let holds = |game: &Game, &(color, op, n): &Condition| {
+        let colors = min_colors(game);
+        let max = Color::lookup(color).map_or(0, |color| colors.get(color));
+        match op {
+            "<" => max < n,
+            "<=" => max <= n,
+            "=" => max == n,
+            ">=" => max >= n,
+            _ => max > n,
+        }
+    };
+    let mut found: Vec<String> = games
+        .iter()
+        .filter(|game| conditions.iter().all(|condition| holds(game, condition)))
+        .map(|game| game.to_string())
+        .collect();
+    found.push(format!("{} games", found.len()));
+    Ok(found.join("\n"))
+}
+
+fn query_day_2(games: &[Game], words: &[&str]) -> Reply {
+    match words {
+        ["game", id] => query_game(games, parse_number(id)?),
+        ["games", "where", conditions @ ..] => query_games_where(games, conditions),
+        ["answer"] => {
+            let bag = bag_from_str(DEFAULT_BAG).ok_or("Invalid default bag")?;
+            Ok(format!(
+                "part 1: {}\npart 2: {}",
+                day_2_cube_conundrum::<u128>(games, &bag).map_err(|err| err.to_string())?,
+                day_2_cube_conundrum_part_2::<u128>(games).map_err(|err| err.to_string())?
+            ))
+        }
+        _ => Err(format!("Unknown query, try {}", HELP[1])),
+    }
+}
+
+// Day 3
+
+fn query_parts_near(schematic: &Schematic, (x, y): (usize, usize)) -> Reply {
+    let mut near: Vec<String> = schematic
+        .numbers
+        .iter()
+        .filter(|number| number.row + 1 >= y && number.row <= y.saturating_add(1))
+        .filter(|number| number.col_start <= x.saturating_add(1) && x <= number.col_end)
+        .map(|number| {
+            let kind = if number.is_part() {
+                "part"
+            } else {
+                "not a part"
+            };
+            format!(
+                "{} at row {}, columns {}..{}, {}",
+                number.value, number.row, number.col_start, number.col_end, kind
+            )
+        })
+        .collect();
+    near.push(format!("{} numbers", near.len()));
+    Ok(near.join("\n"))
+}
+
+fn query_gear_at(schematic: &Schematic, (x, y): (usize, usize)) -> Reply {
+    let index = schematic
+        .symbols
+        .iter()
+        .position(|symbol| (symbol.col, symbol.row) == (x, y))
+        .ok_or_else(|| format!("There is no symbol at ({},{})", x, y))?;
+    let symbol = &schematic.symbols[index];
+    let adjacent = &schematic.adjacent_numbers()[index];
+    let values: Vec<u64> = adjacent
+        .iter()
+        .map(|&n| schematic.numbers[n].value)
+        .collect();
+    let listed: Vec<String> = values.iter().map(u64::to_string).collect();
+    let rule = GearRule::puzzle();
+    let verdict = if rule.matches(symbol, values.len()) {
+        let ratio = rule.reduction.reduce::<u128>(&values);
+        format!("a gear, ratio {}", ratio.map_err(|err| err.to_string())?)
+    } else {
+        "not a gear".to_string()
+    };
+    Ok(format!(
+        "{:?} next to {} numbers ({}), {}",
+        symbol.byte as char,
+        values.len(),
+        listed.join(", "),
+        verdict
+    ))
+}
+
+fn query_day_3(schematic: &Schematic, input: &str, words: &[&str]) -> Reply {
+    match words {
+        ["parts", "near", cell @ ..] => query_parts_near(schematic, parse_cell(cell)?),
+        ["gear", "at", cell @ ..] => query_gear_at(schematic, parse_cell(cell)?),
+        ["answer"] => Ok(format!(
+            "part 1: {}\npart 2: {}",
+            day_3_gear_ratios::<u128>(input).map_err(|err| err.to_string())?,
+            day_3_gear_ratios_part_2::<u128>(input, &GearRule::puzzle())
+                .map_err(|err| err.to_string())?
+        )),
+        _ => Err(format!("Unknown query, try {}", HELP[2])),
+    }
+}
+
+fn load(day: usize, body: String) -> Result<Loaded, Box<dyn std::error::Error>> {
+    Ok(match day {
+        1 => {
+            println!("Loaded day 1: {} lines", body.lines().count());
+            Loaded::Day1(body)
+        }
+        2 => {
+            let (games, errors) = parse_input_recovering(&body);
+            for error in &errors {
+                eprintln!("Skipped: {}", error);
+            }
+            println!("Loaded day 2: {} games", games.len());
+            Loaded::Day2(games)
+        }
+        _ => {
+            let schematic = Schematic::parse(&body)?;
+            println!(
+                "Loaded day 3: {}x{} schematic, {} numbers, {} symbols",
+                schematic.width,
+                schematic.height,
+                schematic.numbers.len(),
+                schematic.symbols.len()
+            );
+            Loaded::Day3(schematic, body)
+        }
+    })
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut args = Args::new(USAGE);
+    let day: usize = match args.next_flag().map(|day| day.parse()) {
+        Some(Ok(day @ 1..=3)) => day,
+        _ => return Err(USAGE.into()),
+    };
+    let mut body = INPUTS[day - 1].to_string();
+    while let Some(flag) = args.next_flag() {
+        match flag.as_str() {
+            "--input" => body = std::fs::read_to_string(args.value(&flag)?)?,
+            _ => return Err(args.unknown(&flag).into()),
+        }
+    }
+
+    let loaded = load(day, body)?;
+    let interactive = std::io::stdin().is_terminal();
+    if interactive {
+        println!("Queries: {}", HELP[day - 1]);
+    }
+    let mut lines = std::io::stdin().lock().lines();
+    loop {
+        if interactive {
+            print!("> ");
+            std::io::stdout().flush()?;
+        }
+        let Some(line) = lines.next() else {
+            break;
+        };
+        let line = line?;
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let reply = match (&loaded, words.as_slice()) {
+            (_, []) => continue,
+            (_, ["quit" | "exit"]) => break,
+            (_, ["help"]) => Ok(HELP[day - 1].to_string()),
+            (Loaded::Day1(body), words) => query_day_1(body, words),
+            (Loaded::Day2(games), words) => query_day_2(games, words),
+            (Loaded::Day3(schematic, input), words) => query_day_3(schematic, input, words),
+        };
+        match reply {
+            Ok(reply) => println!("{}", reply),
+            Err(error) => println!("error: {}", error),
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,167 @@
+//! Differential check of every solver against its naive reference in
+//! `reference`. On the first disagreement the input is shrunk, by dropping
+//! lines and characters and blanking cells, for as long as the two still
+//! disagree in the same way.
+
+use crate::check::{gen_day_1, gen_day_2, gen_day_3, Solve};
+use crate::day_1::day_1_trebuchet_part_2;
+use crate::day_1::{day_1_trebuchet, day_1_trebuchet_bytes};
+use crate::day_2::{bag_from_str, day_2_cube_conundrum, parse_input};
+use crate::day_2::{day_2_borrowed, day_2_borrowed_part_2, Games};
+use crate::day_2::{day_2_cube_conundrum_part_2, DEFAULT_BAG};
+use crate::day_3::{day_3_gear_ratios, day_3_gear_ratios_part_2, GearRule};
+use crate::reference;
+use crate::rng::Rng;
+
+pub struct Check {
+    pub name: &'static str,
+    pub generate: fn(&mut Rng) -> String,
+    pub solver: Solve,
+    pub reference: Solve,
+    /// A character that can stand in for any other while shrinking.
+    pub blank: Option<char>,
+}
+
+pub const CHECKS: &[Check] = &[
+    Check {
+        name: "day 1 part 1 (chars)",
+        generate: gen_day_1,
+        solver: |body| day_1_trebuchet::<u128>(body).ok(),
+        reference: reference::day_1_part_1,
+        blank: Some('x'),
+    },
+    Check {
+        name: "day 1 part 1 (bytes)",
+        generate: gen_day_1,
+        solver: |body| day_1_trebuchet_bytes::<u128>(body).ok(),
+        reference: reference::day_1_part_1,
+        blank: Some('x'),
+    },
+    Check {
+        name: "day 1 part 2",
+        generate: gen_day_1,
+        solver: |body| day_1_trebuchet_part_2::<u128>(body).ok(),
+        reference: reference::day_1_part_2,
+        blank: Some('x'),
+    },
+    Check {
+        name: "day 2 part 1",
+        generate: gen_day_2,
+        solver: |body| {
+            let bag = bag_from_str(DEFAULT_BAG)?;
+            day_2_cube_conundrum::<u128>(&parse_input(body).ok()?, &bag).ok()
+        },
+        reference: |body| {
+            reference::day_2_part_1(body, &[("red", 12), ("green", 13), ("blue", 14)])
+        },
+        blank: None,
+    },
+    Check {
+        name: "day 2 part 2",
+        generate: gen_day_2,
+        solver: |body| day_2_cube_conundrum_part_2::<u128>(&parse_input(body).ok()?).ok(),
+        reference: reference::day_2_part_2,
+        blank: None,
+    },
+    Check {
+        name: "day 2 part 1 (borrowed)",
+        generate: gen_day_2,
+        solver: |body| {
+            let bag = bag_from_str(DEFAULT_BAG)?;
+            day_2_borrowed::<u128>(&Games::parse(body).ok()?, &bag).ok()
+        },
+        reference: |body| {
+            reference::day_2_part_1(body, &[("red", 12), ("green", 13), ("blue", 14)])
+        },
+        blank: None,
+    },
+    Check {
+        name: "day 2 part 2 (borrowed)",
+        generate: gen_day_2,
+        solver: |body| day_2_borrowed_part_2::<u128>(&Games::parse(body).ok()?).ok(),
+        reference: reference::day_2_part_2,
+        blank: None,
+    },
+    Check {
+        name: "day 3 part 1",
+        generate: gen_day_3,
+        solver: |input| day_3_gear_ratios::<u128>(input).ok(),
+        reference: reference::day_3_part_1,
+        blank: Some('.'),
+    },
+    Check {
+        name: "day 3 part 2",
+        generate: gen_day_3,
+        solver: |input| day_3_gear_ratios_part_2::<u128>(input, &GearRule::puzzle()).ok(),
+        reference: reference::day_3_part_2,
+        blank: Some('.'),
+    },
+];
+
+// Shrinking
+
+/// Smaller variants of `input`: whole lines dropped, then runs of characters
+/// dropped, then single characters replaced by `blank`.
+fn candidates(input: &str, blank: Option<char>) -> Vec<String> {
+    let mut out = Vec::new();
+    let lines: Vec<&str> = input.split_inclusive('\n').collect();
+    for skip in 0..lines.len() {
+        let kept: String = lines
+            .iter()
+            .enumerate()
+            .filter(|&(i, _)| i != skip)
+            .map(|(_, line)| *line)
+            .collect();
+        out.push(kept);
+    }
+    let chars: Vec<char> = input.chars().collect();
+    let mut run = chars.len() / 2;
+    while run > 0 {
+        for start in (0..chars.len()).step_by(run) {
+            let end = (start + run).min(chars.len());
+            out.push(chars[..start].iter().chain(&chars[end..]).collect());
+        }
+        run /= 2;
+    }
+    if let Some(blank) = blank {
+        for (i, &c) in chars.iter().enumerate() {
+            if c != blank && c != '\n' {
+                let mut replaced = chars.clone();
+                replaced[i] = blank;
+                out.push(replaced.into_iter().collect());
+            }
+        }
+    }
+    out
+}
+
+/// Shrinks `input` while the solver and reference keep disagreeing with the
+/// same outcome shape (which side, if either, rejects the input).
+pub fn minimize(check: &Check, input: String) -> String {
+    let shape = |input: &str| {
+        let (solver, reference) = ((check.solver)(input), (check.reference)(input));
+        (solver != reference).then_some((solver.is_some(), reference.is_some()))
+    };
+    let wanted = shape(&input);
+    let mut current = input;
+    'shrink: loop {
+        for candidate in candidates(&current, check.blank) {
+            if candidate != current && shape(&candidate) == wanted {
+                current = candidate;
+                continue 'shrink;
+            }
+        }
+        return current;
+    }
+}
+
+/// The first of `rounds` seeds, counting up from `seed`, whose input the
+/// solver and the reference of `check` disagree on, with that input shrunk.
+pub fn disagreement(check: &Check, seed: u64, rounds: u64) -> Option<(u64, String)> {
+    (0..rounds).find_map(|round| {
+        let round_seed = seed.wrapping_add(round);
+        let input = (check.generate)(&mut Rng::new(round_seed));
+        let agree = (check.solver)(&input) == (check.reference)(&input);
+        (!agree).then(|| (round_seed, minimize(check, input)))
+    })
+}
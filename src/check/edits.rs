@@ -0,0 +1,238 @@
+//! Check of the incremental answers against full recomputation.
+//!
+//! Each round generates a small document for a puzzle part and applies a series
+//! of random edits to it, through `Incremental` for the lines of Days 1 and 3
+//! and through `IncrementalGames` for Day 2. After every edit the kept-up
+//! answer must equal that of a fresh document over the edited text, and the
+//! real solver's answer too. Errors must be the same error, with the same
+//! message.
+
+use std::ops::Range;
+
+use crate::check::{gen_day_1, gen_day_2, gen_day_3, pick};
+use crate::day_1::{day_1_trebuchet, day_1_trebuchet_part_2, CalibrationSum};
+use crate::day_2::DEFAULT_BAG;
+use crate::day_2::{bag_from_str, day_2_cube_conundrum, parse_input};
+use crate::day_2::{day_2_cube_conundrum_part_2, IncrementalGames, MaxColors};
+use crate::day_3::{day_3_gear_ratios, day_3_gear_ratios_part_2};
+use crate::day_3::{GearRatios, GearRule, PartNumbers};
+use crate::incremental::{Document, Incremental, LineSum};
+use crate::rng::Rng;
+
+// Pieces that edits insert, line breaks included
+const DAY_1: &[&str] = &["1", "7", "a", "x", "one", "eight", "two", "ne", "\n"];
+const DAY_2: &[&str] = &[
+    "Game ", "1", "12", ": ", "3 red", "14 blue", "2 green", "0 yellow", ", ", "; ", " ", "\n",
+];
+const DAY_3: &[&str] = &[
+    ".",
+    ".",
+    ".",
+    "1",
+    "5",
+    "9",
+    "*",
+    "#",
+    "\n",
+    "\r\n",
+    "9999999999",
+    "\u{e9}",
+];
+
+/// Replaces a random range of `text`, usually a short one, with up to three
+/// pieces, which may hold line breaks. One edit in four instead replaces whole
+/// lines with a freshly generated document, so that the text keeps parsing
+/// often enough for the answers to be worth comparing.
+fn random_edit<D>(rng: &mut Rng, text: &str, check: &Check<D>) -> (Range<usize>, String) {
+    let mut start = rng.index(text.len() + 1);
+    while !text.is_char_boundary(start) {
+        start -= 1;
+    }
+    let mut end = (start + rng.range(0, 4) as usize).min(text.len());
+    if rng.range(0, 9) == 0 {
+        end = rng.index(text.len() - start + 1) + start;
+    }
+    while !text.is_char_boundary(end) {
+        end += 1;
+    }
+    if rng.range(0, 3) == 0 {
+        let start = text[..start].rfind('\n').map_or(0, |i| i + 1);
+        let end = text[end..].find('\n').map_or(text.len(), |i| end + i + 1);
+        return (start..end, (check.generate)(rng));
+    }
+    let count = rng.range(0, 3);
+    let replacement = (0..count).map(|_| pick(rng, check.pieces)).collect();
+    (start..end, replacement)
+}
+
+pub struct Options {
+    pub seed: u64,
+    pub rounds: u64,
+    /// Edits per round.
+    pub edits: u32,
+}
+
+/// What the edits of a check that passed did.
+pub struct Summary {
+    pub edits: usize,
+    /// Edits after which the text had an answer rather than an error.
+    pub answered: usize,
+    /// Records recomputed and records held, summed over the edits.
+    pub recomputed: usize,
+    pub records: usize,
+}
+
+/// An answer, or the message of the error in its place.
+pub type Answer = Result<u128, String>;
+
+/// How one puzzle part is kept up to date, and what it must agree with.
+pub struct Check<D> {
+    pub name: &'static str,
+    pub new: fn(String) -> D,
+    pub answer: fn(&D) -> Answer,
+    /// Whether the document's own split into records matches its text.
+    pub consistent: fn(&D) -> bool,
+    pub generate: fn(&mut Rng) -> String,
+    /// What edits insert.
+    pub pieces: &'static [&'static str],
+    pub solver: fn(&str) -> Answer,
+}
+
+/// Whether `incremental` splits its text into the lines `str::lines` gives.
+fn lines_agree<P: LineSum>(incremental: &Incremental<P>) -> bool {
+    let lines = incremental.lines();
+    let split: Vec<&str> = (0..lines.len())
+        .filter_map(|index| lines.get(index))
+        .collect();
+    split == incremental.text().lines().collect::<Vec<_>>()
+}
+
+/// Applies random edits to each round's document, giving a description of the
+/// first mismatch.
+pub fn check<D: Document>(check: &Check<D>, options: &Options) -> Result<Summary, String> {
+    let (mut edits, mut recomputed, mut records, mut answered) = (0, 0, 0, 0);
+    for round in 0..options.rounds {
+        let round_seed = options.seed.wrapping_add(round);
+        let rng = &mut Rng::new(round_seed);
+        let mut document = (check.new)((check.generate)(rng));
+        // Undoing edits, half the time, walks back to text that parsed
+        let mut undo: Vec<(Range<usize>, String)> = Vec::new();
+        for _ in 0..options.edits {
+            let before = document.text().to_string();
+            let (range, replacement) = match undo.pop() {
+                Some(edit) if rng.range(0, 1) == 0 => edit,
+                popped => {
+                    undo.extend(popped);
+                    let (range, replacement) = random_edit(rng, &before, check);
+                    let inserted = range.start..range.start + replacement.len();
+                    undo.push((inserted, before[range.clone()].to_string()));
+                    (range, replacement)
+                }
+            };
+            document
+                .edit(range.clone(), &replacement)
+                .map_err(|err| err.to_string())?;
+            let text = document.text();
+            edits += 1;
+            recomputed += document.recomputed();
+            records += document.records();
+
+            let answer = (check.answer)(&document);
+            answered += answer.is_ok() as usize;
+            let fresh = (check.answer)(&(check.new)(text.to_string()));
+            let expected = (check.solver)(text);
+            if answer != fresh || answer != expected || !(check.consistent)(&document) {
+                return Err(format!(
+                    "{}: mismatch with seed {}, replacing {:?} with {:?} in\n{:?}\n\
+                     gave {:?}, expected {:?} (fresh {:?})",
+                    check.name, round_seed, range, replacement, before, answer, expected, fresh
+                ));
+            }
+        }
+    }
+    Ok(Summary {
+        edits,
+        answered,
+        recomputed,
+        records,
+    })
+}
+
+fn bag() -> MaxColors {
+    bag_from_str(DEFAULT_BAG).expect("valid default bag")
+}
+
+pub const DAY_1_PART_1: Check<Incremental<CalibrationSum>> = Check {
+    name: "day 1 part 1",
+    new: |text| Incremental::new(CalibrationSum { spelled: false }, text),
+    answer: |incremental| incremental.answer::<u128>().map_err(|err| err.to_string()),
+    consistent: lines_agree,
+    generate: gen_day_1,
+    pieces: DAY_1,
+    solver: |body| day_1_trebuchet::<u128>(body).map_err(|err| err.to_string()),
+};
+
+pub const DAY_1_PART_2: Check<Incremental<CalibrationSum>> = Check {
+    name: "day 1 part 2",
+    new: |text| Incremental::new(CalibrationSum { spelled: true }, text),
+    answer: |incremental| incremental.answer::<u128>().map_err(|err| err.to_string()),
+    consistent: lines_agree,
+    generate: gen_day_1,
+    pieces: DAY_1,
+    solver: |body| day_1_trebuchet_part_2::<u128>(body).map_err(|err| err.to_string()),
+};
+
+pub const DAY_2_PART_1: Check<IncrementalGames> = Check {
+    name: "day 2 part 1",
+    new: |text| IncrementalGames::new(bag(), text),
+    answer: |games| games.part_1::<u128>().map_err(|err| err.to_string()),
+    consistent: |_| true,
+    generate: gen_day_2,
+    pieces: DAY_2,
+    solver: |body| {
+        let games = parse_input(body).map_err(|err| err.to_string())?;
+        day_2_cube_conundrum::<u128>(&games, &bag()).map_err(|err| err.to_string())
+    },
+};
+
+pub const DAY_2_PART_2: Check<IncrementalGames> = Check {
+    name: "day 2 part 2",
+    new: |text| IncrementalGames::new(bag(), text),
+    answer: |games| games.part_2::<u128>().map_err(|err| err.to_string()),
+    consistent: |_| true,
+    generate: gen_day_2,
+    pieces: DAY_2,
+    solver: |body| {
+        let games = parse_input(body).map_err(|err| err.to_string())?;
+        day_2_cube_conundrum_part_2::<u128>(&games).map_err(|err| err.to_string())
+    },
+};
+
+pub const DAY_3_PART_1: Check<Incremental<PartNumbers>> = Check {
+    name: "day 3 part 1",
+    new: |text| Incremental::new(PartNumbers, text),
+    answer: |incremental| incremental.answer::<u128>().map_err(|err| err.to_string()),
+    consistent: lines_agree,
+    generate: gen_day_3,
+    pieces: DAY_3,
+    solver: |body| day_3_gear_ratios::<u128>(body).map_err(|err| err.to_string()),
+};
+
+pub const DAY_3_PART_2: Check<Incremental<GearRatios>> = Check {
+    name: "day 3 part 2",
+    new: |text| {
+        Incremental::new(
+            GearRatios {
+                rule: GearRule::puzzle(),
+            },
+            text,
+        )
+    },
+    answer: |incremental| incremental.answer::<u128>().map_err(|err| err.to_string()),
+    consistent: lines_agree,
+    generate: gen_day_3,
+    pieces: DAY_3,
+    solver: |body| {
+        day_3_gear_ratios_part_2::<u128>(body, &GearRule::puzzle()).map_err(|err| err.to_string())
+    },
+};
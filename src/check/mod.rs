@@ -0,0 +1,114 @@
+//! Randomised checks of the parsers and solvers. The tests in `tests/` run them
+//! on a fixed number of cases from fixed seeds, and driver binaries like
+//! `parser_props` run them on as many as asked for.
+//!
+//! The small random puzzle inputs below are shared by the checks that compare
+//! solvers with each other. They are kept small so that counterexamples stay
+//! readable.
+
+use crate::rng::Rng;
+
+pub mod diff;
+pub mod edits;
+pub mod props;
+
+/// A solver for one puzzle part, `None` when it rejects the input.
+pub type Solve = fn(&str) -> Option<u128>;
+
+pub fn pick<'a>(rng: &mut Rng, items: &[&'a str]) -> &'a str {
+    items[rng.index(items.len())]
+}
+
+/// One line of a Day 1 document: digits, letters and the spelled-out digits or
+/// pieces of them.
+pub fn gen_day_1_line(rng: &mut Rng) -> String {
+    const PIECES: &[&str] = &[
+        "0", "1", "5", "9", "a", "x", "q", "one", "two", "three", "eight", "nine", "zero", "on",
+        "tw", "eigh", "ne", "n", "e", "o",
+    ];
+    (0..rng.range(1, 6)).map(|_| pick(rng, PIECES)).collect()
+}
+
+pub fn gen_day_1(rng: &mut Rng) -> String {
+    let lines: Vec<String> = (0..rng.range(1, 6)).map(|_| gen_day_1_line(rng)).collect();
+    lines.join("\n") + "\n"
+}
+
+/// One Day 2 game with the given `id`, of up to two sets of up to two draws.
+pub fn gen_day_2_game(rng: &mut Rng, id: u32) -> String {
+    const COLORS: &[&str] = &["red", "green", "blue", "red", "green", "blue", "yellow"];
+    let sets: Vec<String> = (0..rng.range(1, 3))
+        .map(|_| {
+            let draws: Vec<String> = (0..rng.range(1, 3))
+                .map(|_| format!("{} {}", rng.range(0, 16), pick(rng, COLORS)))
+                .collect();
+            draws.join(", ")
+        })
+        .collect();
+    format!("Game {}: {}", id, sets.join("; "))
+}
+
+pub fn gen_day_2(rng: &mut Rng) -> String {
+    let games: Vec<String> = (0..rng.range(1, 5))
+        .map(|_| {
+            let id = rng.range(1, 100);
+            gen_day_2_game(rng, id)
+        })
+        .collect();
+    games.join("\n") + "\n"
+}
+
+/// `rows` rows of `width` random cells. Numbers are cut off after three
+/// digits, so that gear ratios stay small.
+pub fn gen_schematic(rng: &mut Rng, width: usize, rows: usize) -> String {
+    const CELLS: &[&str] = &[
+        ".", ".", ".", ".", "1", "2", "5", "9", "0", "*", "*", "#", "+",
+    ];
+    let mut body = String::new();
+    for _ in 0..rows {
+        let mut digits = 0;
+        for _ in 0..width {
+            let mut cell = pick(rng, CELLS);
+            digits = if cell.as_bytes()[0].is_ascii_digit() {
+                digits + 1
+            } else {
+                0
+            };
+            if digits > 3 {
+                cell = ".";
+                digits = 0;
+            }
+            body.push_str(cell);
+        }
+        body.push('\n');
+    }
+    body
+}
+
+/// A small schematic, now and then with a number too large for 64 bits, which
+/// may also make its row too wide, or with a cell that is not ASCII.
+pub fn gen_day_3(rng: &mut Rng) -> String {
+    let width = rng.range(1, 8) as usize;
+    let rows = rng.range(1, 6) as usize;
+    let schematic = gen_schematic(rng, width, rows);
+    let mut lines: Vec<String> = schematic.lines().map(String::from).collect();
+    let y = rng.index(rows);
+    match rng.range(0, 7) {
+        0 => {
+            let pad = rng.range(0, 1) == 0;
+            for (row, line) in lines.iter_mut().enumerate() {
+                if row == y {
+                    line.insert_str(0, "99999999999999999999");
+                } else if pad {
+                    line.insert_str(0, "....................");
+                }
+            }
+        }
+        1 => {
+            let x = rng.index(width);
+            lines[y].replace_range(x..=x, "\u{e9}");
+        }
+        _ => {}
+    }
+    lines.join("\n") + "\n"
+}
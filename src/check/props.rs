@@ -0,0 +1,324 @@
+//! Property checks for the parser combinators, each run on many generated
+//! cases:
+//!
+//! - `parse_list` gives back the items of a list joined with any separator and
+//!   surrounding whitespace
+//! - `exact` fails without advancing on a mismatch, and advances by exactly the
+//!   expected text on a match
+//! - `consume` rejects any non-empty remainder
+//! - no combinator panics, or stops off a char boundary, on arbitrary Unicode
+//!   input
+//! - a `grammar!` parser with an inline list item reads back what was printed
+//! - a derived `FromInput` parser, with nested lists of an enum, reads back what
+//!   was printed, and rejects it with the whitespace between two words removed
+
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::grammar;
+use crate::parser::require_whitespace;
+use crate::parser::FromInput;
+use crate::parser::{
+    consume, digit, exact, letter, parse_list, parse_u32, repeat_to_str, skip_whitespace, Parser,
+};
+use crate::rng::Rng;
+
+/// Characters that tend to break byte-oriented code, mixed with plain ASCII.
+const ALPHABET: &[char] = &[
+    'a', 'z', 'Q', '_', '0', '1', '7', '9', ' ', '\t', '\n', '\r', ',', ';', ':', '|', '-', '.',
+    '\u{0}', '\u{a0}', '\u{3000}', '\u{2028}', 'é', 'ß', 'Ω', '中', '٣', '🦀', '\u{301}',
+];
+
+const SEPARATORS: &[&str] = &[",", ";", "|", "->", "·", "、", "and", ", ", "🦀"];
+
+const WHITESPACE: &[&str] = &["", " ", "\t", "  ", "\n", "\u{3000}", "\u{a0} "];
+
+fn random_char(rng: &mut Rng) -> char {
+    if rng.range(0, 3) == 0 {
+        loop {
+            if let Some(c) = char::from_u32(rng.range(0, 0x10ffff)) {
+                return c;
+            }
+        }
+    }
+    ALPHABET[rng.index(ALPHABET.len())]
+}
+
+fn random_string(rng: &mut Rng, max_len: u32) -> String {
+    (0..rng.range(0, max_len))
+        .map(|_| random_char(rng))
+        .collect()
+}
+
+/// A char boundary of `body`, picked uniformly among all of them.
+fn random_boundary(rng: &mut Rng, body: &str) -> usize {
+    let boundaries: Vec<usize> = (0..=body.len())
+        .filter(|&i| body.is_char_boundary(i))
+        .collect();
+    boundaries[rng.index(boundaries.len())]
+}
+
+fn catch<T>(f: impl FnOnce() -> T) -> Result<T, String> {
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| {
+        payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "panic".to_string())
+    })
+}
+
+// Properties
+
+fn parse_list_round_trips(rng: &mut Rng) -> Result<(), String> {
+    let items: Vec<u32> = (0..rng.range(1, 8))
+        .map(|_| match rng.range(0, 4) {
+            0 => 0,
+            1 => u32::MAX,
+            _ => rng.next_u64() as u32 >> rng.range(0, 31),
+        })
+        .collect();
+    let sep = SEPARATORS[rng.index(SEPARATORS.len())];
+    let mut body = String::new();
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            body.push_str(WHITESPACE[rng.index(WHITESPACE.len())]);
+            body.push_str(sep);
+            body.push_str(WHITESPACE[rng.index(WHITESPACE.len())]);
+        }
+        body.push_str(&item.to_string());
+    }
+
+    let result = catch(|| {
+        parse_list(
+            Parser {
+                body: &body,
+                pos: 0,
+            },
+            sep,
+            parse_u32,
+        )
+    })?;
+    match result {
+        Some((parser, parsed)) if parsed == items && parser.as_str().is_empty() => Ok(()),
+        Some((parser, parsed)) => Err(format!(
+            "{:?} parsed as {:?} with {:?} left",
+            body,
+            parsed,
+            parser.as_str()
+        )),
+        None => Err(format!(
+            "{:?} with separator {:?} failed to parse",
+            body, sep
+        )),
+    }
+}
+
+fn exact_never_advances_on_mismatch(rng: &mut Rng) -> Result<(), String> {
+    let body = random_string(rng, 12);
+    let pos = random_boundary(rng, &body);
+    // Half of the needles are taken from the input, so that matches happen
+    let needle = if rng.range(0, 1) == 0 {
+        let end = random_boundary(rng, &body[pos..]);
+        let mut needle = body[pos..pos + end].to_string();
+        if rng.range(0, 1) == 0 {
+            needle.push(random_char(rng));
+        }
+        needle
+    } else {
+        random_string(rng, 3)
+    };
+
+    let parser = Parser { body: &body, pos };
+    let result = catch(|| exact(parser, &needle))?;
+    let expected = body[pos..].starts_with(needle.as_str());
+    match result {
+        None if !expected => Ok(()),
+        Some((parser, ())) if expected && parser.pos == pos + needle.len() => Ok(()),
+        Some((parser, ())) => Err(format!(
+            "exact({:?} at {}, {:?}) moved to {}",
+            body, pos, needle, parser.pos
+        )),
+        None => Err(format!("exact({:?} at {}, {:?}) failed", body, pos, needle)),
+    }
+}
+
+fn consume_rejects_remainder(rng: &mut Rng) -> Result<(), String> {
+    let n = rng.next_u64() as u32;
+    let mut rest = random_string(rng, 6);
+    if rest.starts_with(|c: char| c.is_ascii_digit()) {
+        rest.insert(0, 'x');
+    }
+    let body = format!("{}{}", n, rest);
+
+    let result = catch(|| {
+        consume(parse_u32(Parser {
+            body: &body,
+            pos: 0,
+        }))
+    });
+    match result {
+        Ok(parsed) if rest.is_empty() && parsed == n => Ok(()),
+        Ok(parsed) => Err(format!("consume accepted {:?} as {}", body, parsed)),
+        Err(_) if !rest.is_empty() => Ok(()),
+        Err(message) => Err(format!("consume rejected {:?}: {}", body, message)),
+    }
+}
+
+fn combinators_never_panic(rng: &mut Rng) -> Result<(), String> {
+    let body = random_string(rng, 16);
+    let needle = random_string(rng, 2);
+    for pos in (0..=body.len()).filter(|&i| body.is_char_boundary(i)) {
+        let parser = Parser { body: &body, pos };
+        let ends = catch(|| {
+            [
+                exact(parser, &needle).map(|(p, _)| p.pos),
+                skip_whitespace(parser).map(|(p, _)| p.pos),
+                require_whitespace(parser).map(|(p, _)| p.pos),
+                digit(parser).map(|(p, _)| p.pos),
+                letter(parser).map(|(p, _)| p.pos),
+                repeat_to_str(parser, digit).map(|(p, _)| p.pos),
+                repeat_to_str(parser, letter).map(|(p, _)| p.pos),
+                parse_u32(parser).map(|(p, _)| p.pos),
+                parse_list(parser, ",", parse_u32).map(|(p, _)| p.pos),
+            ]
+        })
+        .map_err(|message| format!("panic on {:?} at {}: {}", body, pos, message))?;
+        if let Some(end) = ends
+            .into_iter()
+            .flatten()
+            .find(|&end| !body.is_char_boundary(end))
+        {
+            return Err(format!(
+                "{:?} at {} stopped inside a char at {}",
+                body, pos, end
+            ));
+        }
+    }
+    Ok(())
+}
+
+grammar! {
+    fn parse_pairs -> Vec<(u32, u32)> {
+        pairs = list(";", { ws key = u32 ws "=" ws value = u32 => (key, value) }) => pairs
+    }
+}
+
+fn grammar_round_trips(rng: &mut Rng) -> Result<(), String> {
+    let pairs: Vec<(u32, u32)> = (0..rng.range(1, 6))
+        .map(|_| (rng.next_u64() as u32, rng.range(0, 9)))
+        .collect();
+    let printed: Vec<String> = pairs
+        .iter()
+        .map(|(key, value)| {
+            let space = WHITESPACE[rng.index(WHITESPACE.len())];
+            format!("{}{}={}{}", space, key, space, value)
+        })
+        .collect();
+    let body = printed.join(";");
+
+    let parser = Parser {
+        body: &body,
+        pos: 0,
+    };
+    match catch(|| parse_pairs(parser))? {
+        Some((parser, parsed)) if parsed == pairs && parser.as_str().is_empty() => Ok(()),
+        Some((_, parsed)) => Err(format!("{:?} parsed as {:?}", body, parsed)),
+        None => Err(format!("{:?} failed to parse", body)),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, FromInput)]
+enum Rgb {
+    Red,
+    Green,
+    Blue,
+}
+
+#[derive(Debug, PartialEq, FromInput)]
+#[format("{count} x {channels}")]
+struct Swatch {
+    count: u64,
+    #[sep("|", ",")]
+    channels: Vec<Vec<Rgb>>,
+}
+
+fn derive_round_trips(rng: &mut Rng) -> Result<(), String> {
+    const RGB: &[(Rgb, &str)] = &[
+        (Rgb::Red, "red"),
+        (Rgb::Green, "green"),
+        (Rgb::Blue, "blue"),
+    ];
+    let space = |rng: &mut Rng| WHITESPACE[rng.index(WHITESPACE.len())];
+    // A space in the format needs at least one whitespace character
+    let gap = |rng: &mut Rng| WHITESPACE[rng.range(1, WHITESPACE.len() as u32 - 1) as usize];
+    let count = rng.next_u64();
+    let mut channels = Vec::new();
+    let after = gap(rng);
+    let mut printed = after.to_string();
+    for i in 0..rng.range(1, 4) {
+        if i > 0 {
+            printed += &format!("{}|{}", space(rng), space(rng));
+        }
+        let mut group = Vec::new();
+        for j in 0..rng.range(1, 4) {
+            if j > 0 {
+                printed += &format!("{},{}", space(rng), space(rng));
+            }
+            let (channel, name) = RGB[rng.index(RGB.len())];
+            group.push(channel);
+            printed += name;
+        }
+        channels.push(group);
+    }
+    let swatch = Swatch { count, channels };
+    let body = format!("{}{}x{}", count, gap(rng), printed);
+
+    let parser = Parser {
+        body: &body,
+        pos: 0,
+    };
+    match catch(|| Swatch::from_input(parser))? {
+        Some((parser, parsed)) if parsed == swatch && parser.as_str().is_empty() => {}
+        Some((_, parsed)) => return Err(format!("{:?} parsed as {:?}", body, parsed)),
+        None => return Err(format!("{:?} failed to parse", body)),
+    }
+    for glued in [
+        format!("{}x{}", count, printed),
+        format!("{}{}x{}", count, gap(rng), &printed[after.len()..]),
+    ] {
+        let parser = Parser {
+            body: &glued,
+            pos: 0,
+        };
+        if let Some((_, parsed)) = catch(|| Swatch::from_input(parser))? {
+            return Err(format!("{:?} parsed as {:?}", glued, parsed));
+        }
+    }
+    Ok(())
+}
+
+pub type Property = fn(&mut Rng) -> Result<(), String>;
+
+pub const PROPERTIES: &[(&str, Property)] = &[
+    ("parse_list round-trips", parse_list_round_trips),
+    (
+        "exact never advances on mismatch",
+        exact_never_advances_on_mismatch,
+    ),
+    ("consume rejects any remainder", consume_rejects_remainder),
+    ("combinators never panic", combinators_never_panic),
+    ("grammar! round-trips", grammar_round_trips),
+    ("derived FromInput round-trips", derive_round_trips),
+];
+
+/// The first of `cases` seeds, counting up from `seed`, on which `property`
+/// fails, with what went wrong. Panics are caught, but still reach the panic
+/// hook.
+pub fn failure(property: Property, seed: u64, cases: u64) -> Option<(u64, String)> {
+    (0..cases).find_map(|case| {
+        let case_seed = seed.wrapping_add(case);
+        property(&mut Rng::new(case_seed))
+            .err()
+            .map(|message| (case_seed, message))
+    })
+}
@@ -0,0 +1,122 @@
+//! Command-line handling shared by the binaries: flags and their values, the
+//! `--int` and `--input` options of every solver, Day 2's `--lenient`, and the
+//! Day 3 gear rule options.
+
+use std::error::Error;
+use std::str::FromStr;
+
+use crate::day_2::{parse_input_recovering, Game};
+use crate::day_3::{Arity, GearRule, Reduction};
+
+/// The arguments after the program name, read one flag at a time. Errors end
+/// with the binary's usage line.
+pub struct Args {
+    args: std::vec::IntoIter<String>,
+    usage: &'static str,
+}
+
+impl Args {
+    pub fn new(usage: &'static str) -> Self {
+        let args: Vec<String> = std::env::args().skip(1).collect();
+        Args {
+            args: args.into_iter(),
+            usage,
+        }
+    }
+
+    pub fn next_flag(&mut self) -> Option<String> {
+        self.args.next()
+    }
+
+    /// The value after `flag`.
+    pub fn value(&mut self, flag: &str) -> Result<String, String> {
+        let usage = self.usage;
+        self.args
+            .next()
+            .ok_or_else(|| format!("{} expects a value, {}", flag, usage))
+    }
+
+    /// The value after `flag`, parsed.
+    pub fn parse<T: FromStr>(&mut self, flag: &str) -> Result<T, String> {
+        self.parse_with(flag, |value| value.parse().ok())
+    }
+
+    /// The value after `flag`, read by `parse`.
+    pub fn parse_with<T>(
+        &mut self,
+        flag: &str,
+        parse: impl FnOnce(&str) -> Option<T>,
+    ) -> Result<T, String> {
+        let value = self.value(flag)?;
+        parse(&value).ok_or_else(|| self.invalid(flag, &value))
+    }
+
+    pub fn invalid(&self, flag: &str, value: &str) -> String {
+        format!("Invalid value {:?} for {}, {}", value, flag, self.usage)
+    }
+
+    pub fn unknown(&self, flag: &str) -> String {
+        format!("Unknown argument {:?}, {}", flag, self.usage)
+    }
+}
+
+/// The options every solver takes: the accumulator for `with_accumulator!`,
+/// and the puzzle input.
+pub struct Solver {
+    pub int: String,
+    pub body: String,
+}
+
+impl Solver {
+    /// Solves the day's own `input` in `u64`, unless told otherwise.
+    pub fn new(input: &str) -> Self {
+        Solver {
+            int: "u64".to_string(),
+            body: input.to_string(),
+        }
+    }
+
+    /// Takes `--int` or `--input PATH`, giving `false` for any other flag.
+    pub fn flag(&mut self, flag: &str, args: &mut Args) -> Result<bool, Box<dyn Error>> {
+        match flag {
+            "--int" => self.int = args.value(flag)?,
+            "--input" => self.body = std::fs::read_to_string(args.value(flag)?)?,
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+}
+
+/// The games of a Day 2 input. Malformed games are an error, unless `lenient`
+/// skips them with a warning.
+pub fn day_2_games(body: &str, lenient: bool) -> Result<Vec<Game>, Box<dyn Error>> {
+    let (games, errors) = parse_input_recovering(body);
+    if !errors.is_empty() && !lenient {
+        let errors: Vec<String> = errors.iter().map(ToString::to_string).collect();
+        return Err(format!("{}; pass --lenient to skip them", errors.join("; ")).into());
+    }
+    for error in errors {
+        eprintln!("Skipped: {}", error);
+    }
+    Ok(games)
+}
+
+/// Takes one of the gear rule options `--symbols`, `--arity`, `--min-arity`
+/// and `--reduce`, giving `false` for any other flag.
+pub fn gear_rule_flag(rule: &mut GearRule, flag: &str, args: &mut Args) -> Result<bool, String> {
+    match flag {
+        "--symbols" => rule.symbols = args.value(flag)?.into_bytes(),
+        "--arity" => rule.arity = Arity::Exactly(args.parse(flag)?),
+        "--min-arity" => rule.arity = Arity::AtLeast(args.parse(flag)?),
+        "--reduce" => {
+            rule.reduction = args.parse_with(flag, |value| match value {
+                "product" => Some(Reduction::Product),
+                "sum" => Some(Reduction::Sum),
+                "max" => Some(Reduction::Max),
+                _ => None,
+            })?
+        }
+        _ => return Ok(false),
+    }
+    Ok(true)
+}
@@ -0,0 +1,239 @@
+//! Day 1: Trebuchet. Each line's calibration value is made of its first and
+//! last digit; in part 2 digits may also be spelled out as words.
+//!
+//! The solvers never panic: a line without any digit, or a sum too large for
+//! the accumulator, comes back as an error.
+
+use std::fmt;
+
+use crate::incremental::{LineSum, Lines};
+use crate::num::{lift, Accumulator, Overflow};
+
+/// The part 1 example from the puzzle text.
+pub const EXAMPLE: &str = "\
+1abc2
+pqr3stu8vwx
+a1b2c3d4e5f
+treb7uchet
+";
+
+/// The part 2 example from the puzzle text, with spelled-out digits.
+pub const EXAMPLE_PART_2: &str = "\
+two1nine
+eightwothree
+abcone2threexyz
+xtwone3four
+4nineeightseven2
+zoneight234
+7pqrstsixteen
+";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NoDigits {
+    /// 1-based line number.
+    pub line: usize,
+}
+
+impl fmt::Display for NoDigits {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "There are no digits on line {}", self.line)
+    }
+}
+
+impl std::error::Error for NoDigits {}
+
+fn sum_values<A: Accumulator>(
+    values: impl Iterator<Item = Result<u64, NoDigits>>,
+) -> Result<A, Box<dyn std::error::Error>> {
+    let mut sum = lift::<A>(0)?;
+    for value in values {
+        sum = sum.checked_add(&lift(value?)?).ok_or(Overflow)?;
+    }
+    Ok(sum)
+}
+
+// Part 1
+
+/// The first and last digit of `line`, as a two-digit number.
+fn calibration_value(line: &str) -> Option<u64> {
+    let d1 = line.chars().find_map(|c| c.to_digit(10))?;
+    let d2 = line.chars().rev().find_map(|c| c.to_digit(10))?;
+    Some((10 * d1 + d2) as u64)
+}
+
+pub fn day_1_trebuchet<A: Accumulator>(body: &str) -> Result<A, Box<dyn std::error::Error>> {
+    sum_values(
+        body.lines()
+            .enumerate()
+            .map(|(i, line)| calibration_value(line).ok_or(NoDigits { line: i + 1 })),
+    )
+}
+
+// Byte-level fast path
+
+const LO_BITS: u64 = 0x0101_0101_0101_0101;
+const HI_BITS: u64 = 0x8080_8080_8080_8080;
+
+/// Finds the first `needle` in `haystack`, comparing eight bytes at a time.
+fn find_byte(haystack: &[u8], needle: u8) -> Option<usize> {
+    let pattern = LO_BITS * needle as u64;
+    let mut chunks = haystack.chunks_exact(8);
+    let mut offset = 0;
+    for chunk in &mut chunks {
+        let word = u64::from_le_bytes(chunk.try_into().unwrap()) ^ pattern;
+        let zeros = word.wrapping_sub(LO_BITS) & !word & HI_BITS;
+        if zeros != 0 {
+            return Some(offset + (zeros.trailing_zeros() / 8) as usize);
+        }
+        offset += 8;
+    }
+    let rest = chunks.remainder();
+    rest.iter().position(|&b| b == needle).map(|i| offset + i)
+}
+
+/// Sets the high bit of every byte of `word` that is an ASCII digit. The high
+/// bits are masked off first, so that no byte borrows from or carries into
+/// its neighbour.
+fn digit_bits(word: u64) -> u64 {
+    let low = word & (LO_BITS * 0x7f);
+    let below_colon = LO_BITS * (127 + b':' as u64) - low;
+    let above_slash = low + LO_BITS * (127 - b'/' as u64);
+    below_colon & above_slash & !word & HI_BITS
+}
+
+/// Finds the first ASCII digit in `haystack`, eight bytes at a time.
+fn find_digit(haystack: &[u8]) -> Option<usize> {
+    let mut chunks = haystack.chunks_exact(8);
+    let mut offset = 0;
+    for chunk in &mut chunks {
+        let digits = digit_bits(u64::from_le_bytes(chunk.try_into().unwrap()));
+        if digits != 0 {
+            return Some(offset + (digits.trailing_zeros() / 8) as usize);
+        }
+        offset += 8;
+    }
+    let rest = chunks.remainder();
+    rest.iter().position(u8::is_ascii_digit).map(|i| offset + i)
+}
+
+/// Finds the last ASCII digit in `haystack`, eight bytes at a time from the
+/// end.
+fn rfind_digit(haystack: &[u8]) -> Option<usize> {
+    let mut chunks = haystack.rchunks_exact(8);
+    let mut end = haystack.len();
+    for chunk in &mut chunks {
+        let digits = digit_bits(u64::from_le_bytes(chunk.try_into().unwrap()));
+        if digits != 0 {
+            return Some(end - 1 - (digits.leading_zeros() / 8) as usize);
+        }
+        end -= 8;
+    }
+    chunks.remainder().iter().rposition(u8::is_ascii_digit)
+}
+
+pub fn day_1_trebuchet_bytes<A: Accumulator>(body: &str) -> Result<A, Box<dyn std::error::Error>> {
+    let mut bytes = body.as_bytes();
+    let mut sum = lift::<A>(0)?;
+    let mut line_no = 1;
+    while !bytes.is_empty() {
+        let end = find_byte(bytes, b'\n').unwrap_or(bytes.len());
+        let line = &bytes[..end];
+        let first = find_digit(line);
+        let last = rfind_digit(line);
+        match (first, last) {
+            (Some(first), Some(last)) => {
+                let d1 = (line[first] - b'0') as u64;
+                let d2 = (line[last] - b'0') as u64;
+                sum = sum.checked_add(&lift(10 * d1 + d2)?).ok_or(Overflow)?;
+            }
+            _ => return Err(NoDigits { line: line_no }.into()),
+        }
+        bytes = bytes.get(end + 1..).unwrap_or_default();
+        line_no += 1;
+    }
+    Ok(sum)
+}
+
+// Part 2
+
+pub const NUMBERS: &[(&str, u32)] = &[
+    ("0", 0),
+    ("1", 1),
+    ("2", 2),
+    ("3", 3),
+    ("4", 4),
+    ("5", 5),
+    ("6", 6),
+    ("7", 7),
+    ("8", 8),
+    ("9", 9),
+    ("zero", 0),
+    ("one", 1),
+    ("two", 2),
+    ("three", 3),
+    ("four", 4),
+    ("five", 5),
+    ("six", 6),
+    ("seven", 7),
+    ("eight", 8),
+    ("nine", 9),
+];
+
+pub fn prefix_to_digit(prefix: &str) -> Option<u32> {
+    NUMBERS.iter().find_map(|(word, digit)| {
+        if prefix.starts_with(word) {
+            Some(*digit)
+        } else {
+            None
+        }
+    })
+}
+
+/// Like `calibration_value`, with digits that may also be spelled out.
+fn spelled_calibration_value(line: &str) -> Option<u64> {
+    let mut first: Option<u32> = None;
+    let mut last: Option<u32> = None;
+    let mut chars = line.chars();
+    loop {
+        let cur_str = chars.as_str();
+        if let Some(n) = prefix_to_digit(cur_str) {
+            first = first.or(Some(n));
+            last = Some(n);
+        }
+        if chars.next().is_none() {
+            break;
+        }
+    }
+    Some((10 * first? + last?) as u64)
+}
+
+pub fn day_1_trebuchet_part_2<A: Accumulator>(body: &str) -> Result<A, Box<dyn std::error::Error>> {
+    sum_values(
+        body.lines()
+            .enumerate()
+            .map(|(i, line)| spelled_calibration_value(line).ok_or(NoDigits { line: i + 1 })),
+    )
+}
+
+// Incremental
+
+/// Either part as a `LineSum`, for `Incremental`.
+pub struct CalibrationSum {
+    /// Whether digits may be spelled out, as in part 2.
+    pub spelled: bool,
+}
+
+impl LineSum for CalibrationSum {
+    const CONTEXT: usize = 0;
+    type Error = NoDigits;
+
+    fn value(&self, lines: &Lines, index: usize) -> Result<u128, NoDigits> {
+        let line = lines.get(index).unwrap_or_default();
+        let value = if self.spelled {
+            spelled_calibration_value(line)
+        } else {
+            calibration_value(line)
+        };
+        value.map(u128::from).ok_or(NoDigits { line: index + 1 })
+    }
+}
@@ -0,0 +1,959 @@
+//! Day 2: Cube Conundrum. Game records and their parser, shared by both parts.
+//!
+//! `parse_input` and the solvers never panic: malformed input is a
+//! `ParseError`, and answers too large for the accumulator are an `Overflow`.
+//! Every colour name in a successful parse is interned for the life of the
+//! thread; a parse that fails forgets the names it interned.
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::ops::Range;
+
+use crate::grammar;
+use crate::incremental::{Document, InvalidEdit};
+use crate::num::{checked_product, checked_sum, lift, lift_all, lift_u128, Accumulator, Overflow};
+use crate::parser::FromInput;
+use crate::parser::{deepest_failure, rule, set_trace, trace_mode, Parser, ParserResult, Trace};
+use crate::parser::{exact, letter, parse_list, parse_u32, repeat_to_str};
+use crate::parser::{require_whitespace, skip_whitespace};
+
+/// The example games from the puzzle text.
+pub const EXAMPLE: &str = "\
+Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
+";
+
+/// A cube colour, interned into the per-thread symbol table so that colours can
+/// be compared and used as indices without carrying their names around.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Color(usize);
+
+#[derive(Default)]
+struct Symbols {
+    names: Vec<String>,
+    ids: HashMap<String, Color>,
+}
+
+thread_local! {
+    static SYMBOLS: RefCell<Symbols> = RefCell::new(Symbols::default());
+}
+
+impl Color {
+    pub fn intern(name: &str) -> Color {
+        SYMBOLS.with(|symbols| {
+            let mut symbols = symbols.borrow_mut();
+            if let Some(&color) = symbols.ids.get(name) {
+                return color;
+            }
+            let color = Color(symbols.names.len());
+            symbols.names.push(name.to_string());
+            symbols.ids.insert(name.to_string(), color);
+            color
+        })
+    }
+
+    /// The colour named `name`, if it has been interned. Never allocates.
+    pub fn lookup(name: &str) -> Option<Color> {
+        SYMBOLS.with(|symbols| symbols.borrow().ids.get(name).copied())
+    }
+
+    /// Position of the colour in the symbol table, for colour-indexed maps.
+    pub fn index(self) -> usize {
+        self.0
+    }
+
+    /// All colours interned so far, in order of first appearance.
+    pub fn all() -> impl Iterator<Item = Color> {
+        let count = SYMBOLS.with(|symbols| symbols.borrow().names.len());
+        (0..count).map(Color)
+    }
+
+    /// Runs `parse`, forgetting every colour it interned if it fails. No
+    /// `Color` for those names can outlive a failed parse.
+    fn forget_on_failure<T>(parse: impl FnOnce() -> Option<T>) -> Option<T> {
+        let mark = SYMBOLS.with(|symbols| symbols.borrow().names.len());
+        let result = parse();
+        if result.is_none() {
+            SYMBOLS.with(|symbols| {
+                let mut symbols = symbols.borrow_mut();
+                for name in symbols.names.split_off(mark) {
+                    symbols.ids.remove(&name);
+                }
+            });
+        }
+        result
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        SYMBOLS.with(|symbols| f.pad(&symbols.borrow().names[self.0]))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FromInput)]
+#[format("{qty} {color}")]
+pub struct ColorQty {
+    pub color: Color,
+    pub qty: u32,
+}
+
+/// A game record, `Game 1: 3 blue, 4 red; 2 green`. As in every derived
+/// format, a space between words stands for at least one whitespace character,
+/// which may be a line break. Whitespace after the colon and around the
+/// separators is optional, so `Game 1:3 blue` is a game too.
+#[derive(Debug, Clone, PartialEq, Eq, FromInput)]
+#[format("Game {id}: {sets}")]
+pub struct Game {
+    pub id: u32,
+    #[sep(";", ",")]
+    pub sets: Vec<Vec<ColorQty>>,
+}
+
+pub fn parse_games(parser: Parser) -> ParserResult<Vec<Game>> {
+    rule("parse_games", "", parser, |parser| {
+        let mut games = Vec::new();
+        let mut cur_parser = parser;
+        while let Some((parser, game)) = parse_game(cur_parser) {
+            games.push(game);
+            let (parser, _) = skip_whitespace(parser)?;
+            cur_parser = parser;
+        }
+        cur_parser.with(games)
+    })
+}
+
+pub fn parse_game(parser: Parser) -> ParserResult<Game> {
+    Color::forget_on_failure(|| Game::from_input(parser))
+}
+
+pub fn parse_color_qty(parser: Parser) -> ParserResult<ColorQty> {
+    ColorQty::from_input(parser)
+}
+
+pub fn parse_color(parser: Parser) -> ParserResult<Color> {
+    rule("parse_color", "", parser, |parser| {
+        let (parser, name) = repeat_to_str(parser, letter)?;
+        if name.is_empty() {
+            return None;
+        }
+        parser.with(Color::intern(name))
+    })
+}
+
+impl FromInput for Color {
+    fn from_input(parser: Parser) -> ParserResult<Self> {
+        parse_color(parser)
+    }
+}
+
+/// A game that failed to parse: where the deepest rule gave up (1-based) and
+/// which rule that was.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub col: usize,
+    pub rule: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Invalid game at line {}, column {}, in {}",
+            self.line, self.col, self.rule
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Re-parses the game at `pos`, which is known to fail, with failures traced to
+/// find where it went wrong. Also gives the byte offset of that point.
+fn locate_error(body: &str, pos: usize) -> (ParseError, usize) {
+    let mode = trace_mode();
+    if mode == Trace::Off {
+        set_trace(Trace::Failures);
+    }
+    let start = Parser { body, pos };
+    let failed = parse_game(start).is_none();
+    let failure = deepest_failure().filter(|failure| failed && failure.pos >= pos);
+    if mode == Trace::Off {
+        set_trace(Trace::Off);
+    }
+
+    match failure {
+        Some(failure) => {
+            let rule = failure.stack.last().cloned().unwrap_or_default();
+            let error = ParseError {
+                line: failure.line,
+                col: failure.col,
+                rule,
+            };
+            (error, failure.pos)
+        }
+        None => {
+            let (line, col) = start.line_col();
+            let rule = "parse_game".to_string();
+            (ParseError { line, col, rule }, pos)
+        }
+    }
+}
+
+/// Parses a whole document of games. Unlike `consume(parse_games(..))` it does
+/// not panic, and reports where the first malformed game went wrong.
+pub fn parse_input(body: &str) -> Result<Vec<Game>, ParseError> {
+    let start = Parser { body, pos: 0 };
+    let mut stop = 0;
+    let games = Color::forget_on_failure(|| {
+        let (parser, games) = parse_games(start).unwrap_or((start, Vec::new()));
+        stop = parser.pos;
+        parser.as_str().is_empty().then_some(games)
+    });
+    games.ok_or_else(|| locate_error(body, stop).0)
+}
+
+/// Like `parse_input`, but when that fails, parses every non-blank line on its
+/// own as exactly one game. Gives the games of the lines that parsed, and an
+/// error for each line that did not. A game that wraps onto another line only
+/// parses while the whole document does.
+pub fn parse_input_recovering(body: &str) -> (Vec<Game>, Vec<ParseError>) {
+    if let Ok(games) = parse_input(body) {
+        return (games, Vec::new());
+    }
+    let mut games = Vec::new();
+    let mut errors = Vec::new();
+    for (index, line) in body.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match parse_line(line) {
+            Ok(game) => games.push(game),
+            Err(error) => errors.push(ParseError {
+                line: index + 1,
+                ..error
+            }),
+        }
+    }
+    (games, errors)
+}
+
+/// Parses `line` as one game with nothing but whitespace around it.
+fn parse_line(line: &str) -> Result<Game, ParseError> {
+    let start = Parser { body: line, pos: 0 };
+    let start = skip_whitespace(start).map_or(start, |(parser, _)| parser);
+    let mut end = start;
+    let game = Color::forget_on_failure(|| {
+        let (parser, game) = parse_game(start)?;
+        end = skip_whitespace(parser).map_or(parser, |(parser, _)| parser);
+        end.as_str().is_empty().then_some(game)
+    });
+    game.ok_or_else(|| {
+        if end.pos == start.pos {
+            return locate_error(line, start.pos).0;
+        }
+        let (line, col) = end.line_col();
+        let rule = "end of line".to_string();
+        ParseError { line, col, rule }
+    })
+}
+
+// Part 1
+
+/// Puzzle bag for part 1.
+pub const DEFAULT_BAG: &str = "red=12,green=13,blue=14";
+
+/// Parses bag contents like `red=12, green=13, blue=14`. Colours that are not
+/// listed are taken to hold no cubes.
+pub fn parse_bag(parser: Parser) -> ParserResult<MaxColors> {
+    rule("parse_bag", "", parser, |parser| {
+        let (parser, _) = skip_whitespace(parser)?;
+        let (parser, entries) = parse_list(parser, ",", parse_bag_entry)?;
+        let mut max_colors = MaxColors::default();
+        for ColorQty { color, qty } in entries {
+            max_colors.set(color, qty);
+        }
+        let (parser, _) = skip_whitespace(parser)?;
+        parser.with(max_colors)
+    })
+}
+
+/// Parses a whole bag specification, rejecting trailing input.
+pub fn bag_from_str(spec: &str) -> Option<MaxColors> {
+    Color::forget_on_failure(|| {
+        parse_bag(Parser { body: spec, pos: 0 })
+            .filter(|(parser, _)| parser.as_str().is_empty())
+            .map(|(_, max_colors)| max_colors)
+    })
+}
+
+grammar! {
+    pub fn parse_bag_entry -> ColorQty {
+        color = parse_color "=" qty = u32 => ColorQty { color, qty }
+    }
+}
+
+/// Cube limits of the bag per colour; colours never set hold no cubes.
+#[derive(Default)]
+pub struct MaxColors(Vec<u32>);
+impl MaxColors {
+    pub fn get(&self, color: Color) -> u32 {
+        self.0.get(color.index()).copied().unwrap_or(0)
+    }
+    pub fn set(&mut self, color: Color, qty: u32) {
+        if color.index() >= self.0.len() {
+            self.0.resize(color.index() + 1, 0);
+        }
+        self.0[color.index()] = qty;
+    }
+}
+
+/// The first draw of a game that the bag could not have produced.
+pub struct Violation {
+    pub set: usize,
+    pub color_qty: ColorQty,
+    pub max: u32,
+}
+
+pub fn check_game(game: &Game, max_colors: &MaxColors) -> Result<(), Violation> {
+    for (set_idx, set) in game.sets.iter().enumerate() {
+        for &color_qty in set {
+            let max = max_colors.get(color_qty.color);
+            if color_qty.qty > max {
+                return Err(Violation {
+                    set: set_idx,
+                    color_qty,
+                    max,
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Sums the IDs of the games that are possible with the given bag.
+pub fn day_2_cube_conundrum<A: Accumulator>(
+    games: &[Game],
+    max_colors: &MaxColors,
+) -> Result<A, Overflow> {
+    let ids = games
+        .iter()
+        .filter(|game| check_game(game, max_colors).is_ok())
+        .map(|game| game.id as u64);
+    checked_sum(lift_all(ids)?)
+}
+
+// Part 2
+
+/// The fewest cubes of each colour that make `game` possible.
+pub fn min_colors(game: &Game) -> MaxColors {
+    let mut colors = MaxColors::default();
+    for set in &game.sets {
+        for color_qty in set {
+            if color_qty.qty > colors.get(color_qty.color) {
+                colors.set(color_qty.color, color_qty.qty);
+            }
+        }
+    }
+    colors
+}
+
+/// The colours drawn anywhere in `games`, in order of first appearance.
+pub fn drawn_colors(games: &[Game]) -> Vec<Color> {
+    let mut colors = Vec::new();
+    for color_qty in games.iter().flat_map(|game| game.sets.iter().flatten()) {
+        if !colors.contains(&color_qty.color) {
+            colors.push(color_qty.color);
+        }
+    }
+    colors
+}
+
+/// Sums the powers of each game's minimal bag. A power is the product over
+/// every colour drawn in `games`, so a game that never draws one of them has a
+/// power of zero, as with the puzzle's red, green and blue.
+pub fn day_2_cube_conundrum_part_2<A: Accumulator>(games: &[Game]) -> Result<A, Overflow> {
+    let drawn = drawn_colors(games);
+    let process_game = |game: &Game| {
+        let colors = min_colors(game);
+        checked_product(lift_all(
+            drawn.iter().map(|&color| colors.get(color) as u64),
+        )?)
+    };
+
+    checked_sum(
+        games
+            .iter()
+            .map(process_game)
+            .collect::<Result<Vec<A>, _>>()?,
+    )
+}
+
+// Statistics
+
+/// How one colour is drawn across all games.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColorStats {
+    pub color: Color,
+    /// The most cubes of the colour in one draw, which is also how many a bag
+    /// needs for every game to be possible.
+    pub largest: u32,
+    /// The mean cubes per draw, over the draws that show the colour.
+    pub mean: f64,
+    /// The IDs of the games that draw `largest` cubes of it, and so need them.
+    pub binding: Vec<u32>,
+}
+
+/// Statistics over all games, as `day_2_b --stats` reports them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stats {
+    /// One entry per colour drawn, in order of first appearance.
+    pub colors: Vec<ColorStats>,
+    /// How many sets hold each total number of cubes.
+    pub set_sizes: BTreeMap<u64, usize>,
+}
+
+/// The longest bar `Stats` prints in its histogram of set sizes.
+pub const HISTOGRAM_WIDTH: usize = 40;
+
+pub fn stats(games: &[Game]) -> Stats {
+    let colors = drawn_colors(games)
+        .into_iter()
+        .map(|color| {
+            let draws: Vec<u32> = games
+                .iter()
+                .flat_map(|game| game.sets.iter().flatten())
+                .filter(|color_qty| color_qty.color == color)
+                .map(|color_qty| color_qty.qty)
+                .collect();
+            let total: u64 = draws.iter().map(|&qty| qty as u64).sum();
+            let largest = draws.iter().copied().max().unwrap_or(0);
+            let binding = games
+                .iter()
+                .filter(|game| {
+                    let mut draws = game.sets.iter().flatten();
+                    draws.any(|color_qty| color_qty.color == color && color_qty.qty == largest)
+                })
+                .map(|game| game.id)
+                .collect();
+            ColorStats {
+                color,
+                largest,
+                mean: total as f64 / draws.len().max(1) as f64,
+                binding,
+            }
+        })
+        .collect();
+
+    let mut set_sizes = BTreeMap::new();
+    for set in games.iter().flat_map(|game| &game.sets) {
+        let size = set.iter().map(|color_qty| color_qty.qty as u64).sum();
+        *set_sizes.entry(size).or_default() += 1;
+    }
+    Stats { colors, set_sizes }
+}
+
+impl fmt::Display for Stats {
+    /// The report, with histogram bars scaled so that the longest is
+    /// `HISTOGRAM_WIDTH` long.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "Cubes per draw of each colour (largest / mean over the draws showing it):"
+        )?;
+        for color in &self.colors {
+            writeln!(
+                f,
+                "  {:>8}: {:>3} / {:.2}",
+                color.color, color.largest, color.mean
+            )?;
+        }
+        writeln!(
+            f,
+            "Minimal bag for every game to be possible, and the games requiring it:"
+        )?;
+        for color in &self.colors {
+            let binding: Vec<String> = color.binding.iter().map(u32::to_string).collect();
+            writeln!(
+                f,
+                "  {:>8}: {:>3} (games {})",
+                color.color,
+                color.largest,
+                binding.join(", ")
+            )?;
+        }
+        writeln!(f, "Cubes per set:")?;
+        let most = self.set_sizes.values().copied().max().unwrap_or(0);
+        for (size, &count) in &self.set_sizes {
+            let bar = (count * HISTOGRAM_WIDTH).div_ceil(most.max(1));
+            writeln!(f, "  {:>3}: {} {}", size, "#".repeat(bar), count)?;
+        }
+        Ok(())
+    }
+}
+
+// Borrowed representation
+
+/// One draw of a borrowed game, naming its colour by a slice of the input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Draw<'a> {
+    pub color: &'a str,
+    pub qty: u32,
+}
+
+/// A game of `Games`, as ranges into its flat buffers.
+#[derive(Debug, Clone)]
+struct GameEntry {
+    id: u32,
+    sets: (usize, usize),
+}
+
+/// A whole document of games parsed without copying: every draw of every game
+/// sits in one flat buffer, sets are end offsets into it, and colour names
+/// borrow from the input. Parsing allocates only when a buffer grows, and
+/// nothing is interned.
+#[derive(Debug, Default)]
+pub struct Games<'a> {
+    draws: Vec<Draw<'a>>,
+    set_ends: Vec<usize>,
+    games: Vec<GameEntry>,
+}
+
+/// A game borrowed from `Games`.
+#[derive(Debug, Clone, Copy)]
+pub struct GameRef<'g, 'a> {
+    pub id: u32,
+    games: &'g Games<'a>,
+    sets: (usize, usize),
+}
+
+impl<'g, 'a> GameRef<'g, 'a> {
+    /// The draws of each set, in order.
+    pub fn sets(&self) -> impl Iterator<Item = &'g [Draw<'a>]> {
+        let games = self.games;
+        (self.sets.0..self.sets.1).map(move |set| {
+            let start = if set == 0 { 0 } else { games.set_ends[set - 1] };
+            &games.draws[start..games.set_ends[set]]
+        })
+    }
+
+    /// Every draw of the game, across all sets.
+    pub fn draws(&self) -> &'g [Draw<'a>] {
+        let (first, end) = self.sets;
+        let start = if first == 0 {
+            0
+        } else {
+            self.games.set_ends[first - 1]
+        };
+        let end = if end == first {
+            start
+        } else {
+            self.games.set_ends[end - 1]
+        };
+        &self.games.draws[start..end]
+    }
+}
+
+impl<'a> Games<'a> {
+    /// Parses the same documents as `parse_input`, failing with the same error.
+    pub fn parse(body: &'a str) -> Result<Games<'a>, ParseError> {
+        let mut games = Games::default();
+        let mut parser = Parser { body, pos: 0 };
+        while !parser.as_str().is_empty() {
+            let Some((next, _)) = games.parse_game(parser) else {
+                return Err(locate_error(body, parser.pos).0);
+            };
+            parser = skip_whitespace(next).map_or(next, |(parser, _)| parser);
+        }
+        Ok(games)
+    }
+
+    /// Appends one game, in the format of the derived `Game` parser. On failure
+    /// the buffers may be left holding part of it.
+    fn parse_game(&mut self, parser: Parser<'a>) -> ParserResult<'a, ()> {
+        let (parser, _) = exact(parser, "Game")?;
+        let (parser, _) = require_whitespace(parser)?;
+        let (parser, id) = parse_u32(parser)?;
+        let (parser, _) = exact(parser, ":")?;
+        let (mut parser, _) = skip_whitespace(parser)?;
+        let first_set = self.set_ends.len();
+        'sets: loop {
+            loop {
+                let (next, qty) = parse_u32(parser)?;
+                let (next, _) = require_whitespace(next)?;
+                let (next, color) = repeat_to_str(next, letter)?;
+                if color.is_empty() {
+                    return None;
+                }
+                self.draws.push(Draw { color, qty });
+                parser = next;
+                let (next, _) = skip_whitespace(parser)?;
+                if let Some((next, _)) = exact(next, ",") {
+                    (parser, _) = skip_whitespace(next)?;
+                    continue;
+                }
+                self.set_ends.push(self.draws.len());
+                if let Some((next, _)) = exact(next, ";") {
+                    (parser, _) = skip_whitespace(next)?;
+                    continue 'sets;
+                }
+                break 'sets;
+            }
+        }
+        self.games.push(GameEntry {
+            id,
+            sets: (first_set, self.set_ends.len()),
+        });
+        parser.with(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.games.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.games.is_empty()
+    }
+
+    pub fn get(&self, index: usize) -> Option<GameRef<'_, 'a>> {
+        let entry = self.games.get(index)?;
+        Some(GameRef {
+            id: entry.id,
+            games: self,
+            sets: entry.sets,
+        })
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = GameRef<'_, 'a>> {
+        (0..self.len()).filter_map(|index| self.get(index))
+    }
+}
+
+/// Part 1 over borrowed games. Colours the bag has never heard of hold no
+/// cubes, as in `day_2_cube_conundrum`. Allocates nothing.
+pub fn day_2_borrowed<A: Accumulator>(
+    games: &Games,
+    max_colors: &MaxColors,
+) -> Result<A, Overflow> {
+    let fits = |draw: &Draw| draw.qty <= Color::lookup(draw.color).map_or(0, |c| max_colors.get(c));
+    let mut sum = lift::<A>(0)?;
+    for game in games.iter() {
+        if game.draws().iter().all(fits) {
+            sum = sum.checked_add(&lift(game.id as u64)?).ok_or(Overflow)?;
+        }
+    }
+    Ok(sum)
+}
+
+/// Part 2 over borrowed games. As in `day_2_cube_conundrum_part_2`, the power
+/// of a game is a product over every colour drawn in the document. Allocates
+/// once per document, not per game.
+pub fn day_2_borrowed_part_2<A: Accumulator>(games: &Games) -> Result<A, Overflow> {
+    let mut colors: Vec<&str> = Vec::new();
+    for draw in &games.draws {
+        if !colors.contains(&draw.color) {
+            colors.push(draw.color);
+        }
+    }
+
+    let mut sum = lift::<A>(0)?;
+    for game in games.iter() {
+        let mut power = lift::<A>(1)?;
+        for &color in &colors {
+            let draws = game.draws().iter().filter(|draw| draw.color == color);
+            let max = draws.map(|draw| draw.qty).max().unwrap_or(0);
+            power = power.checked_mul(&lift(max as u64)?).ok_or(Overflow)?;
+        }
+        sum = sum.checked_add(&power).ok_or(Overflow)?;
+    }
+    Ok(sum)
+}
+
+// Incremental
+
+/// A game of an `IncrementalGames` document, and where it lies in the text.
+struct GameSpan {
+    start: usize,
+    /// Where the next game starts, past this one and the whitespace after it.
+    /// Parsing the game looked at the text up to and including this position.
+    next: usize,
+    id: u32,
+    possible: bool,
+    /// The fewest cubes of each colour the game draws.
+    bag: Vec<(Color, u32)>,
+    /// The power over the colours drawn in the document, `None` on overflow.
+    power: Option<u128>,
+}
+
+/// Day 2 games kept parsed while their text is edited, with the answers to
+/// both parts. Games may span lines, so an edit re-parses from the first game
+/// that looked at the edited bytes until a game starts where one did before,
+/// past the edit. A parse error stops the games there, as in `parse_input`, so
+/// an edit at or after it re-parses the rest of the document.
+pub struct IncrementalGames {
+    bag: MaxColors,
+    text: String,
+    games: Vec<GameSpan>,
+    /// Sum of the IDs of the possible games.
+    possible: u128,
+    /// How many games draw each colour drawn in the document.
+    drawn: HashMap<Color, usize>,
+    /// Sum of the powers of the games whose power did not overflow.
+    powers: u128,
+    /// How many times `powers` has wrapped around, net of wrapping back.
+    carries: i64,
+    /// How many games have a power that overflowed.
+    overflows: usize,
+    recomputed: usize,
+}
+
+impl IncrementalGames {
+    pub fn new(bag: MaxColors, text: String) -> Self {
+        let mut games = IncrementalGames {
+            bag,
+            text: String::new(),
+            games: Vec::new(),
+            possible: 0,
+            drawn: HashMap::new(),
+            powers: 0,
+            carries: 0,
+            overflows: 0,
+            recomputed: 0,
+        };
+        games
+            .edit(0..0, &text)
+            .expect("an empty document takes any text");
+        games
+    }
+
+    /// The answer to part 1, or the error `parse_input` gives.
+    pub fn part_1<A: Accumulator>(&self) -> Result<A, Box<dyn std::error::Error>> {
+        self.error()?;
+        Ok(lift_u128(self.possible)?)
+    }
+
+    /// The answer to part 2, or the error `parse_input` gives. Powers and their
+    /// sum are kept in `u128`; past that, they are found again in `A` from the
+    /// games' minimal bags.
+    pub fn part_2<A: Accumulator>(&self) -> Result<A, Box<dyn std::error::Error>> {
+        self.error()?;
+        if self.overflows == 0 && self.carries == 0 {
+            return Ok(lift_u128(self.powers)?);
+        }
+        let powers = self.games.iter().map(|game| self.power::<A>(game));
+        Ok(checked_sum(powers.collect::<Result<Vec<A>, _>>()?)?)
+    }
+
+    /// The games stop where the next one fails to parse, which is an error
+    /// unless it is the end of the text.
+    fn error(&self) -> Result<(), ParseError> {
+        let stop = self.games.last().map_or(0, |game| game.next);
+        if stop == self.text.len() {
+            return Ok(());
+        }
+        Err(locate_error(&self.text, stop).0)
+    }
+
+    fn span(&self, start: usize, next: usize, game: &Game) -> GameSpan {
+        let min = min_colors(game);
+        let bag = drawn_colors(std::slice::from_ref(game))
+            .into_iter()
+            .map(|color| (color, min.get(color)))
+            .collect();
+        GameSpan {
+            start,
+            next,
+            id: game.id,
+            possible: check_game(game, &self.bag).is_ok(),
+            bag,
+            power: None,
+        }
+    }
+
+    /// The power of `game`, zero unless it draws every colour in `drawn`.
+    fn power<A: Accumulator>(&self, game: &GameSpan) -> Result<A, Overflow> {
+        if game.bag.len() < self.drawn.len() {
+            return lift(0);
+        }
+        checked_product(lift_all(game.bag.iter().map(|&(_, qty)| qty as u64))?)
+    }
+
+    fn add_power(&mut self, power: Option<u128>) {
+        match power {
+            Some(power) => {
+                let (sum, wrapped) = self.powers.overflowing_add(power);
+                self.powers = sum;
+                self.carries += wrapped as i64;
+            }
+            None => self.overflows += 1,
+        }
+    }
+
+    fn forget(&mut self, game: GameSpan) {
+        if game.possible {
+            self.possible -= game.id as u128;
+        }
+        for (color, _) in game.bag {
+            if let Some(count) = self.drawn.get_mut(&color) {
+                *count -= 1;
+                if *count == 0 {
+                    self.drawn.remove(&color);
+                }
+            }
+        }
+        match game.power {
+            Some(power) => {
+                let (sum, wrapped) = self.powers.overflowing_sub(power);
+                self.powers = sum;
+                self.carries -= wrapped as i64;
+            }
+            None => self.overflows -= 1,
+        }
+    }
+
+    fn drawn_colors(&self) -> Vec<Color> {
+        let mut colors: Vec<Color> = self.drawn.keys().copied().collect();
+        colors.sort_by_key(|color| color.index());
+        colors
+    }
+}
+
+impl Document for IncrementalGames {
+    fn text(&self) -> &str {
+        &self.text
+    }
+
+    fn records(&self) -> usize {
+        self.games.len()
+    }
+
+    fn recomputed(&self) -> usize {
+        self.recomputed
+    }
+
+    /// Re-parses the games from the first one that looked at the edited bytes.
+    /// When the colours drawn in the document change, every power is found
+    /// again from the games' minimal bags, without parsing them.
+    fn edit(&mut self, range: Range<usize>, replacement: &str) -> Result<(), InvalidEdit> {
+        InvalidEdit::check(&self.text, &range)?;
+        let drawn = self.drawn_colors();
+        let first = self.games.partition_point(|game| game.next < range.start);
+        let mut pos = first.checked_sub(1).map_or(0, |last| self.games[last].next);
+        self.text.replace_range(range.clone(), replacement);
+        let shift = |old: usize| old + replacement.len() - range.len();
+
+        // Old games past the edit are kept from the first one the new games
+        // run into; those before it, or touching the edit, are forgotten
+        let mut old = self.games.split_off(first).into_iter().peekable();
+        let mut parsed = Vec::new();
+        loop {
+            while let Some(game) =
+                old.next_if(|game| game.start < range.end || shift(game.start) < pos)
+            {
+                self.forget(game);
+            }
+            if old.peek().is_some_and(|game| shift(game.start) == pos) {
+                break;
+            }
+            let start = Parser {
+                body: &self.text,
+                pos,
+            };
+            let Some((parser, game)) = parse_game(start) else {
+                for game in old.by_ref() {
+                    self.forget(game);
+                }
+                break;
+            };
+            let next = skip_whitespace(parser).map_or(parser.pos, |(parser, _)| parser.pos);
+            parsed.push(self.span(pos, next, &game));
+            pos = next;
+        }
+
+        self.recomputed = parsed.len();
+        for game in &parsed {
+            if game.possible {
+                self.possible += game.id as u128;
+            }
+            for &(color, _) in &game.bag {
+                *self.drawn.entry(color).or_default() += 1;
+            }
+        }
+        let kept = old.map(|game| GameSpan {
+            start: shift(game.start),
+            next: shift(game.next),
+            ..game
+        });
+        let added = first..first + parsed.len();
+        self.games.extend(parsed.into_iter().chain(kept));
+
+        let powers = if self.drawn_colors() == drawn {
+            added
+        } else {
+            self.powers = 0;
+            self.carries = 0;
+            self.overflows = 0;
+            0..self.games.len()
+        };
+        for index in powers {
+            let power = self.power::<u128>(&self.games[index]).ok();
+            self.games[index].power = power;
+            self.add_power(power);
+        }
+        Ok(())
+    }
+}
+
+// Printing
+
+impl fmt::Display for ColorQty {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.qty, self.color)
+    }
+}
+
+/// Prints the canonical `Game N: 3 blue, 4 red; 1 red, 2 green` form, which
+/// `parse_game` reads back into an equal `Game`.
+impl fmt::Display for Game {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Game {}:", self.id)?;
+        for (set_idx, set) in self.sets.iter().enumerate() {
+            f.write_str(if set_idx == 0 { " " } else { "; " })?;
+            for (idx, color_qty) in set.iter().enumerate() {
+                if idx > 0 {
+                    f.write_str(", ")?;
+                }
+                write!(f, "{}", color_qty)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Game {
+    /// Serializes the game as `{"id":1,"sets":[[{"color":"blue","qty":3}]]}`.
+    /// Colour names are letters and underscores only, so they need no escaping.
+    pub fn to_json(&self) -> String {
+        let sets: Vec<String> = self
+            .sets
+            .iter()
+            .map(|set| {
+                let items: Vec<String> = set
+                    .iter()
+                    .map(|color_qty| {
+                        format!(
+                            "{{\"color\":\"{}\",\"qty\":{}}}",
+                            color_qty.color, color_qty.qty
+                        )
+                    })
+                    .collect();
+                format!("[{}]", items.join(","))
+            })
+            .collect();
+        format!("{{\"id\":{},\"sets\":[{}]}}", self.id, sets.join(","))
+    }
+}
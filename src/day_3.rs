@@ -0,0 +1,823 @@
+//! Day 3: Gear Ratios. The engine schematic, parsed once into its numbers and
+//! symbols, with both parts answered as queries over it.
+//!
+//! Parsing and the solvers never panic: malformed input is a `SchematicError`,
+//! and answers too large for the accumulator are an `Overflow`.
+
+use std::collections::BTreeSet;
+use std::fmt;
+
+use crate::incremental::{LineSum, Lines};
+use crate::num::{checked_product, checked_sum, lift, lift_all, Accumulator, Overflow};
+
+/// The example schematic from the puzzle text.
+pub const EXAMPLE: &str = "\
+467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..";
+
+pub struct Matrix<T> {
+    pub width: usize,
+    pub height: usize,
+    pub data: Vec<T>,
+}
+
+impl<T: Clone> Matrix<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self {
+        Self {
+            width,
+            height,
+            data: vec![fill; width * height],
+        }
+    }
+}
+
+pub fn entry<T>(matrix: &Matrix<T>, x: i32, y: i32) -> Option<&T> {
+    if x < 0 || y < 0 {
+        return None;
+    }
+    let x = x as usize;
+    let y = y as usize;
+    if x >= matrix.width || y >= matrix.height {
+        return None;
+    }
+    Some(&matrix.data[x + y * matrix.width])
+}
+
+/// Any cell that is neither a digit nor `.`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol {
+    pub byte: u8,
+    pub row: usize,
+    pub col: usize,
+}
+
+/// A number in the schematic, spanning `col_start..col_end` of its row. It is a
+/// part number when `adjacent_symbols`, indices into `Schematic::symbols`, is
+/// not empty.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartNumber {
+    pub value: u64,
+    pub row: usize,
+    pub col_start: usize,
+    pub col_end: usize,
+    pub adjacent_symbols: Vec<usize>,
+}
+
+impl PartNumber {
+    pub fn is_part(&self) -> bool {
+        !self.adjacent_symbols.is_empty()
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Schematic {
+    pub width: usize,
+    pub height: usize,
+    pub numbers: Vec<PartNumber>,
+    pub symbols: Vec<Symbol>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchematicError {
+    Empty,
+    /// `line` is 1-based.
+    Ragged {
+        line: usize,
+        len: usize,
+        width: usize,
+    },
+    /// A number that does not fit in 64 bits, starting at 1-based `line`/`col`.
+    NumberTooLarge {
+        line: usize,
+        col: usize,
+    },
+    /// A character outside ASCII at 1-based `line`/`col`. Cells are bytes, so
+    /// only ASCII schematics are accepted.
+    NonAscii {
+        line: usize,
+        col: usize,
+    },
+}
+
+impl fmt::Display for SchematicError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SchematicError::Empty => write!(f, "Schematic is empty"),
+            SchematicError::Ragged { line, len, width } => write!(
+                f,
+                "Schematic line {} has {} cells, expected {} like the first line",
+                line, len, width
+            ),
+            SchematicError::NumberTooLarge { line, col } => write!(
+                f,
+                "Schematic number at line {}, column {} does not fit in 64 bits",
+                line, col
+            ),
+            SchematicError::NonAscii { line, col } => write!(
+                f,
+                "Schematic line {} has a non-ASCII character at column {}",
+                line, col
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SchematicError {}
+
+/// What to do with rows that are not as wide as the first one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RaggedRows {
+    Reject,
+    /// Treat every row as if it were padded with `.` to the widest row.
+    Pad,
+}
+
+/// Fails on the first non-ASCII character of `line`, 0-based `idx`.
+fn check_ascii(line: &[u8], idx: usize) -> Result<(), SchematicError> {
+    match line.iter().position(|byte| !byte.is_ascii()) {
+        Some(col) => Err(SchematicError::NonAscii {
+            line: idx + 1,
+            col: col + 1,
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Splits a schematic into rows without line endings or trailing blank lines,
+/// checked to be ASCII and against `ragged`, along with its width.
+fn schematic_lines(input: &str, ragged: RaggedRows) -> Result<(Vec<&[u8]>, usize), SchematicError> {
+    let lines = input.lines();
+    let mut lines: Vec<&[u8]> = lines
+        .map(|line| line.strip_suffix('\r').unwrap_or(line).as_bytes())
+        .collect();
+    while lines.last().is_some_and(|line| line.is_empty()) {
+        lines.pop();
+    }
+    if lines.is_empty() {
+        return Err(SchematicError::Empty);
+    }
+
+    let width = match ragged {
+        RaggedRows::Reject => {
+            let width = lines[0].len();
+            for (idx, line) in lines.iter().enumerate() {
+                check_ascii(line, idx)?;
+                if line.len() != width {
+                    return Err(SchematicError::Ragged {
+                        line: idx + 1,
+                        len: line.len(),
+                        width,
+                    });
+                }
+            }
+            width
+        }
+        RaggedRows::Pad => {
+            for (idx, line) in lines.iter().enumerate() {
+                check_ascii(line, idx)?;
+            }
+            lines.iter().map(|line| line.len()).max().unwrap_or(0)
+        }
+    };
+    Ok((lines, width))
+}
+
+impl Schematic {
+    pub fn parse(input: &str) -> Result<Schematic, SchematicError> {
+        Schematic::parse_with(input, RaggedRows::Reject)
+    }
+
+    /// Parses a schematic with either line ending. Trailing blank lines are
+    /// ignored.
+    pub fn parse_with(input: &str, ragged: RaggedRows) -> Result<Schematic, SchematicError> {
+        let (lines, width) = schematic_lines(input, ragged)?;
+        Schematic::from_lines(&lines, width)
+    }
+
+    /// Like `parse_with`, but parses bands of rows on up to `threads` threads.
+    /// Each band also sees the row above and below it, so numbers are resolved
+    /// entirely within their band, and the bands are then merged into the same
+    /// schematic `parse_with` would give. `day_3_a` and `day_3_b` use it when
+    /// given `--threads`.
+    pub fn parse_parallel(
+        input: &str,
+        ragged: RaggedRows,
+        threads: usize,
+    ) -> Result<Schematic, SchematicError> {
+        let (lines, width) = schematic_lines(input, ragged)?;
+        let height = lines.len();
+        let band_height = height.div_ceil(threads.max(1));
+
+        let bands = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..height)
+                .step_by(band_height)
+                .map(|start| {
+                    let end = (start + band_height).min(height);
+                    let offset = start.saturating_sub(1);
+                    let lines = &lines[offset..(end + 1).min(height)];
+                    scope.spawn(move || {
+                        let band =
+                            Schematic::from_lines(lines, width).map_err(|err| match err {
+                                SchematicError::NumberTooLarge { line, col } => {
+                                    SchematicError::NumberTooLarge {
+                                        line: line + offset,
+                                        col,
+                                    }
+                                }
+                                err => err,
+                            })?;
+                        Ok((start..end, offset, band))
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().expect("band parser panicked"))
+                .collect::<Result<Vec<_>, SchematicError>>()
+        })?;
+
+        // Symbols are in row-major order, and every band sees whole rows, so a
+        // symbol's global index is that of the first symbol in its row plus its
+        // rank within the row.
+        let mut row_start = vec![0; height + 1];
+        for (rows, offset, band) in &bands {
+            for symbol in &band.symbols {
+                if rows.contains(&(symbol.row + offset)) {
+                    row_start[symbol.row + offset + 1] += 1;
+                }
+            }
+        }
+        for row in 0..height {
+            row_start[row + 1] += row_start[row];
+        }
+
+        let mut symbols = Vec::with_capacity(row_start[height]);
+        let mut numbers = Vec::new();
+        for (rows, offset, band) in bands {
+            let mut band_row_start = vec![0; band.height + 1];
+            for symbol in &band.symbols {
+                band_row_start[symbol.row + 1] += 1;
+            }
+            for row in 0..band.height {
+                band_row_start[row + 1] += band_row_start[row];
+            }
+            let global_index = |idx: usize| {
+                let row = band.symbols[idx].row;
+                row_start[row + offset] + idx - band_row_start[row]
+            };
+
+            for mut number in band.numbers {
+                number.row += offset;
+                if rows.contains(&number.row) {
+                    for idx in &mut number.adjacent_symbols {
+                        *idx = global_index(*idx);
+                    }
+                    numbers.push(number);
+                }
+            }
+            for mut symbol in band.symbols {
+                symbol.row += offset;
+                if rows.contains(&symbol.row) {
+                    symbols.push(symbol);
+                }
+            }
+        }
+
+        Ok(Schematic {
+            width,
+            height,
+            numbers,
+            symbols,
+        })
+    }
+
+    fn from_lines(lines: &[&[u8]], width: usize) -> Result<Schematic, SchematicError> {
+        let height = lines.len();
+
+        let mut symbols = Vec::new();
+        let mut symbol_matrix = Matrix::<Option<usize>>::new(width, height, None);
+        for (y, line) in lines.iter().enumerate() {
+            for (x, &byte) in line.iter().enumerate() {
+                if !byte.is_ascii_digit() && byte != b'.' {
+                    symbol_matrix.data[x + y * width] = Some(symbols.len());
+                    symbols.push(Symbol {
+                        byte,
+                        row: y,
+                        col: x,
+                    });
+                }
+            }
+        }
+
+        let mut numbers = Vec::new();
+        for (y, line) in lines.iter().enumerate() {
+            let mut current_number: Option<(usize, u64)> = None;
+            let mut current_symbols = BTreeSet::new();
+            for (x, &byte) in line.iter().chain(Some(&b'.')).enumerate() {
+                if byte.is_ascii_digit() {
+                    let d = (byte - b'0') as u64;
+                    current_number = match current_number {
+                        Some((start, n)) => {
+                            let n = n.checked_mul(10).and_then(|n| n.checked_add(d));
+                            let too_large = SchematicError::NumberTooLarge {
+                                line: y + 1,
+                                col: start + 1,
+                            };
+                            Some((start, n.ok_or(too_large)?))
+                        }
+                        None => Some((x, d)),
+                    };
+                    for dx in -1..=1 {
+                        for dy in -1..=1 {
+                            let symbol_x = x as i32 + dx;
+                            let symbol_y = y as i32 + dy;
+                            if let Some(Some(symbol)) = entry(&symbol_matrix, symbol_x, symbol_y) {
+                                current_symbols.insert(*symbol);
+                            }
+                        }
+                    }
+                } else if let Some((col_start, value)) = current_number.take() {
+                    numbers.push(PartNumber {
+                        value,
+                        row: y,
+                        col_start,
+                        col_end: x,
+                        adjacent_symbols: std::mem::take(&mut current_symbols)
+                            .into_iter()
+                            .collect(),
+                    });
+                }
+            }
+        }
+
+        Ok(Schematic {
+            width,
+            height,
+            numbers,
+            symbols,
+        })
+    }
+
+    pub fn part_numbers(&self) -> impl Iterator<Item = &PartNumber> {
+        self.numbers.iter().filter(|number| number.is_part())
+    }
+
+    /// Marks every cell next to (or on) a symbol.
+    pub fn flag_matrix(&self) -> Matrix<bool> {
+        let mut flag_matrix = Matrix::new(self.width, self.height, false);
+        for symbol in &self.symbols {
+            for dx in -1..=1 {
+                for dy in -1..=1 {
+                    let x = symbol.col as i32 + dx;
+                    let y = symbol.row as i32 + dy;
+                    if entry(&flag_matrix, x, y).is_some() {
+                        flag_matrix.data[x as usize + y as usize * self.width] = true;
+                    }
+                }
+            }
+        }
+        flag_matrix
+    }
+
+    /// For every symbol, the indices into `numbers` of the numbers next to it.
+    pub fn adjacent_numbers(&self) -> Vec<Vec<usize>> {
+        let mut adjacency = vec![Vec::new(); self.symbols.len()];
+        for (idx, number) in self.numbers.iter().enumerate() {
+            for &symbol in &number.adjacent_symbols {
+                adjacency[symbol].push(idx);
+            }
+        }
+        adjacency
+    }
+
+    /// Symbols that are gears under `rule`, paired with their gear ratio.
+    pub fn gears<A: Accumulator>(&self, rule: &GearRule) -> Result<Vec<(&Symbol, A)>, Overflow> {
+        let mut gears = Vec::new();
+        for (symbol, numbers) in self.symbols.iter().zip(self.adjacent_numbers()) {
+            if rule.matches(symbol, numbers.len()) {
+                let values: Vec<u64> = numbers.iter().map(|&n| self.numbers[n].value).collect();
+                gears.push((symbol, rule.reduction.reduce(&values)?));
+            }
+        }
+        Ok(gears)
+    }
+}
+
+// Graph export
+
+/// A symbol as a DOT quoted-string body.
+fn dot_symbol(byte: u8) -> String {
+    match byte {
+        b'"' => "\\\"".to_string(),
+        b'\\' => "\\\\".to_string(),
+        _ => (byte as char).to_string(),
+    }
+}
+
+/// A symbol as a JSON string body. Symbols are ASCII, so a control character
+/// is a single `\u00XX` escape.
+fn json_symbol(byte: u8) -> String {
+    match byte {
+        b'"' => "\\\"".to_string(),
+        b'\\' => "\\\\".to_string(),
+        0x20..=0x7e => (byte as char).to_string(),
+        _ => format!("\\u{:04x}", byte),
+    }
+}
+
+impl Schematic {
+    /// The bipartite number/symbol graph in Graphviz DOT. Symbols are `s<i>`
+    /// and numbers `n<i>`, after their indices in `symbols` and `numbers`.
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("graph schematic {\n");
+        for (idx, symbol) in self.symbols.iter().enumerate() {
+            out.push_str(&format!(
+                "  s{} [label=\"{} ({}, {})\", shape=box];\n",
+                idx,
+                dot_symbol(symbol.byte),
+                symbol.col,
+                symbol.row
+            ));
+        }
+        for (idx, number) in self.numbers.iter().enumerate() {
+            out.push_str(&format!("  n{} [label=\"{}\"];\n", idx, number.value));
+        }
+        for (idx, number) in self.numbers.iter().enumerate() {
+            for symbol in &number.adjacent_symbols {
+                out.push_str(&format!("  n{} -- s{};\n", idx, symbol));
+            }
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    /// The same graph as `to_dot`, as a JSON object of `nodes` and `edges`.
+    pub fn to_json(&self) -> String {
+        let mut nodes = Vec::new();
+        for (idx, symbol) in self.symbols.iter().enumerate() {
+            nodes.push(format!(
+                "{{\"id\":\"s{}\",\"kind\":\"symbol\",\"symbol\":\"{}\",\"row\":{},\"col\":{}}}",
+                idx,
+                json_symbol(symbol.byte),
+                symbol.row,
+                symbol.col
+            ));
+        }
+        for (idx, number) in self.numbers.iter().enumerate() {
+            nodes.push(format!(
+                "{{\"id\":\"n{}\",\"kind\":\"number\",\"value\":{},\"row\":{},\"col_start\":{},\"col_end\":{}}}",
+                idx, number.value, number.row, number.col_start, number.col_end
+            ));
+        }
+        let mut edges = Vec::new();
+        for (idx, number) in self.numbers.iter().enumerate() {
+            for symbol in &number.adjacent_symbols {
+                edges.push(format!(
+                    "{{\"source\":\"n{}\",\"target\":\"s{}\"}}",
+                    idx, symbol
+                ));
+            }
+        }
+        format!(
+            "{{\"nodes\":[{}],\"edges\":[{}]}}",
+            nodes.join(","),
+            edges.join(",")
+        )
+    }
+}
+
+// Rendering
+
+// ANSI escapes for `render`, also used by the viewer and the dashboard
+pub const RESET: &str = "\x1b[0m";
+pub const GREEN: &str = "\x1b[32m";
+pub const RED: &str = "\x1b[31m";
+pub const YELLOW: &str = "\x1b[1;33m";
+pub const SHADE: &str = "\x1b[48;5;236m";
+
+enum Cell {
+    Part,
+    NotPart,
+    Gear,
+    Other,
+}
+
+impl Schematic {
+    /// The index of column `x`, row `y` into this schematic's matrices.
+    fn cell_index(&self, x: usize, y: usize) -> Option<usize> {
+        (x < self.width && y < self.height).then_some(x + y * self.width)
+    }
+
+    fn classify(&self, rows: &[&[u8]], rule: &GearRule) -> Vec<Vec<Cell>> {
+        let mut number_matrix = Matrix::<Option<bool>>::new(self.width, self.height, None);
+        for number in &self.numbers {
+            for x in number.col_start..number.col_end {
+                number_matrix.data[x + number.row * self.width] = Some(number.is_part());
+            }
+        }
+        let mut gear_matrix = Matrix::new(self.width, self.height, false);
+        for (symbol, numbers) in self.symbols.iter().zip(self.adjacent_numbers()) {
+            if rule.matches(symbol, numbers.len()) {
+                gear_matrix.data[symbol.col + symbol.row * self.width] = true;
+            }
+        }
+        let is_gear =
+            |x: usize, y: usize| self.cell_index(x, y).is_some_and(|i| gear_matrix.data[i]);
+
+        rows.iter()
+            .enumerate()
+            .map(|(y, row)| {
+                (0..row.len())
+                    .map(|x| {
+                        let number = self.cell_index(x, y).and_then(|i| number_matrix.data[i]);
+                        match number {
+                            Some(true) => Cell::Part,
+                            Some(false) => Cell::NotPart,
+                            None if is_gear(x, y) => Cell::Gear,
+                            None => Cell::Other,
+                        }
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Draws `input`, which this schematic was parsed from, one byte per cell
+    /// as the parser sees it; bytes that are not printable ASCII are drawn as
+    /// `?`. With `color`, part numbers are green, numbers that are not parts
+    /// are red, gears under `rule` are yellow, and the cells next to a symbol have a shaded
+    /// background. Without it each row is followed by a marker row instead:
+    ///
+    /// - `^` digit of a part number
+    /// - `x` digit of a number that is not a part
+    /// - `G` gear
+    /// - `:` other cell next to a symbol
+    pub fn render(&self, input: &str, rule: &GearRule, color: bool) -> String {
+        let rows = schematic_lines(input, RaggedRows::Pad).map_or(Vec::new(), |(rows, _)| rows);
+        let cells = self.classify(&rows, rule);
+        let flag_matrix = self.flag_matrix();
+        let flagged =
+            |x: usize, y: usize| self.cell_index(x, y).is_some_and(|i| flag_matrix.data[i]);
+        let mut out = String::new();
+        for (y, row) in rows.iter().enumerate() {
+            let text: String = row
+                .iter()
+                .map(|&byte| match byte {
+                    0x20..=0x7e => byte as char,
+                    _ => '?',
+                })
+                .collect();
+            if color {
+                for (x, c) in text.chars().enumerate() {
+                    let shade = if flagged(x, y) { SHADE } else { "" };
+                    let color = match cells[y][x] {
+                        Cell::Part => GREEN,
+                        Cell::NotPart => RED,
+                        Cell::Gear => YELLOW,
+                        Cell::Other => "",
+                    };
+                    if shade.is_empty() && color.is_empty() {
+                        out.push(c);
+                    } else {
+                        out.push_str(&format!("{}{}{}{}", shade, color, c, RESET));
+                    }
+                }
+                out.push('\n');
+            } else {
+                let markers: String = cells[y]
+                    .iter()
+                    .enumerate()
+                    .map(|(x, cell)| match cell {
+                        Cell::Part => '^',
+                        Cell::NotPart => 'x',
+                        Cell::Gear => 'G',
+                        Cell::Other if flagged(x, y) => ':',
+                        Cell::Other => ' ',
+                    })
+                    .collect();
+                out += &format!("{}\n{}\n", text, markers.trim_end());
+            }
+        }
+        out
+    }
+}
+
+// Gear rules
+
+#[derive(Debug, Clone, Copy)]
+pub enum Arity {
+    Exactly(usize),
+    AtLeast(usize),
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Reduction {
+    Product,
+    Sum,
+    Max,
+    Custom(fn(&[u64]) -> u64),
+}
+
+impl Reduction {
+    pub fn reduce<A: Accumulator>(self, values: &[u64]) -> Result<A, Overflow> {
+        match self {
+            Reduction::Product => checked_product(lift_all(values.iter().copied())?),
+            Reduction::Sum => checked_sum(lift_all(values.iter().copied())?),
+            Reduction::Max => lift(values.iter().copied().max().unwrap_or(0)),
+            Reduction::Custom(f) => lift(f(values)),
+        }
+    }
+}
+
+/// What makes a symbol a gear: which symbols qualify, how many part numbers
+/// must be next to it, and how their values combine into its ratio.
+#[derive(Debug, Clone)]
+pub struct GearRule {
+    pub symbols: Vec<u8>,
+    pub arity: Arity,
+    pub reduction: Reduction,
+}
+
+impl GearRule {
+    /// The puzzle's rule: a `*` next to exactly two part numbers, multiplied.
+    pub fn puzzle() -> Self {
+        GearRule {
+            symbols: vec![b'*'],
+            arity: Arity::Exactly(2),
+            reduction: Reduction::Product,
+        }
+    }
+
+    pub fn matches(&self, symbol: &Symbol, count: usize) -> bool {
+        let arity_ok = match self.arity {
+            Arity::Exactly(n) => count == n,
+            Arity::AtLeast(n) => count >= n,
+        };
+        arity_ok && self.symbols.contains(&symbol.byte)
+    }
+}
+
+pub fn day_3_gear_ratios<A: Accumulator>(input: &str) -> Result<A, Box<dyn std::error::Error>> {
+    Ok(day_3_schematic(&Schematic::parse(input)?)?)
+}
+
+pub fn day_3_gear_ratios_part_2<A: Accumulator>(
+    input: &str,
+    rule: &GearRule,
+) -> Result<A, Box<dyn std::error::Error>> {
+    Ok(day_3_schematic_part_2(&Schematic::parse(input)?, rule)?)
+}
+
+/// Part 1 over an already parsed schematic, such as one from `parse_parallel`.
+pub fn day_3_schematic<A: Accumulator>(schematic: &Schematic) -> Result<A, Overflow> {
+    let values = schematic.part_numbers().map(|number| number.value);
+    checked_sum(lift_all::<A>(values)?)
+}
+
+/// Part 2 over an already parsed schematic, such as one from `parse_parallel`.
+pub fn day_3_schematic_part_2<A: Accumulator>(
+    schematic: &Schematic,
+    rule: &GearRule,
+) -> Result<A, Overflow> {
+    let gears = schematic.gears::<A>(rule)?;
+    checked_sum(gears.into_iter().map(|(_, ratio)| ratio))
+}
+
+// Incremental
+
+/// Parses the rows around `row` on their own, checking `row` against the first
+/// row as `Schematic::parse` would. Neighbours that are not ASCII or not as
+/// wide as the first row are left out, since they are errors of their own.
+/// Gives the band with the index of `row` in it, or `None` for a blank line.
+///
+/// A blank line is only an error if a later line is not blank, so it is the
+/// first line after a run of blank lines that reports the run. The other lines
+/// then need not look past their neighbours.
+fn band_around(lines: &Lines, row: usize) -> Result<Option<(Schematic, usize)>, SchematicError> {
+    let get = |y| {
+        let line: &str = lines.get(y)?;
+        Some(line.strip_suffix('\r').unwrap_or(line))
+    };
+    let is_blank = |y| get(y).is_some_and(str::is_empty);
+    let width = get(0).unwrap_or_default().len();
+    if width == 0 {
+        // Every line is as wide as a blank first line until the first one
+        // that is not blank, and that is found again for each answer
+        if row > 0 {
+            return Ok(None);
+        }
+        let Some(y) = (1..lines.len()).find(|&y| !is_blank(y)) else {
+            return Err(SchematicError::Empty);
+        };
+        let line = get(y).unwrap_or_default();
+        check_ascii(line.as_bytes(), y)?;
+        let len = line.len();
+        return Err(SchematicError::Ragged {
+            line: y + 1,
+            len,
+            width,
+        });
+    }
+    let line = get(row).unwrap_or_default();
+    if line.is_empty() {
+        return Ok(None);
+    }
+    if row > 0 && is_blank(row - 1) {
+        let run_start = (0..row).rev().take_while(|&y| is_blank(y)).last();
+        return Err(SchematicError::Ragged {
+            line: run_start.unwrap_or(row - 1) + 1,
+            len: 0,
+            width,
+        });
+    }
+    check_ascii(line.as_bytes(), row)?;
+    if line.len() != width {
+        let len = line.len();
+        return Err(SchematicError::Ragged {
+            line: row + 1,
+            len,
+            width,
+        });
+    }
+
+    let rows: Vec<(usize, &[u8])> = (row.saturating_sub(1)..=row + 1)
+        .filter_map(|y| Some((y, get(y)?.as_bytes())))
+        .filter(|(_, line)| line.is_ascii() && line.len() == width)
+        .collect();
+    let bytes: Vec<&[u8]> = rows.iter().map(|&(_, line)| line).collect();
+    let band = Schematic::from_lines(&bytes, width).map_err(|err| match err {
+        SchematicError::NumberTooLarge { line, col } => SchematicError::NumberTooLarge {
+            line: rows[line - 1].0 + 1,
+            col,
+        },
+        err => err,
+    })?;
+    let mid = rows.iter().position(|&(y, _)| y == row).unwrap_or_default();
+    Ok(Some((band, mid)))
+}
+
+/// Part 1 as a `LineSum`, for `Incremental`: each row's value is the sum of the
+/// part numbers on it.
+pub struct PartNumbers;
+
+impl LineSum for PartNumbers {
+    const CONTEXT: usize = 1;
+    const ANCHORED: bool = true;
+    type Error = SchematicError;
+
+    fn value(&self, lines: &Lines, index: usize) -> Result<u128, SchematicError> {
+        let Some((band, mid)) = band_around(lines, index)? else {
+            return Ok(0);
+        };
+        let numbers = band.part_numbers().filter(|number| number.row == mid);
+        Ok(numbers.map(|number| number.value as u128).sum())
+    }
+
+    fn empty(&self) -> Option<SchematicError> {
+        Some(SchematicError::Empty)
+    }
+
+    /// The shape of every row is checked before any number is, so a number
+    /// too large for 64 bits is only reported for an otherwise valid schematic.
+    fn document_error(&self, lines: &Lines) -> Option<SchematicError> {
+        Schematic::parse(lines.text()).err()
+    }
+}
+
+/// Part 2 as a `LineSum`, for `Incremental`: each row's value is the sum of the
+/// ratios of the gears on it.
+pub struct GearRatios {
+    pub rule: GearRule,
+}
+
+impl LineSum for GearRatios {
+    const CONTEXT: usize = 1;
+    const ANCHORED: bool = true;
+    type Error = Box<dyn std::error::Error>;
+
+    fn value(&self, lines: &Lines, index: usize) -> Result<u128, Self::Error> {
+        let Some((band, mid)) = band_around(lines, index)? else {
+            return Ok(0);
+        };
+        let gears = band.gears::<u128>(&self.rule)?;
+        let ratios = gears.into_iter().filter(|(symbol, _)| symbol.row == mid);
+        Ok(checked_sum(ratios.map(|(_, ratio)| ratio))?)
+    }
+
+    fn empty(&self) -> Option<Self::Error> {
+        Some(SchematicError::Empty.into())
+    }
+
+    /// As for `PartNumbers`, and every number is checked before any gear is.
+    fn document_error(&self, lines: &Lines) -> Option<Self::Error> {
+        Some(Schematic::parse(lines.text()).err()?.into())
+    }
+}
@@ -0,0 +1,286 @@
+//! Answers for line-oriented puzzles, kept up to date while the input is
+//! edited. An answer is the sum of one value per line, and a line's value may
+//! depend on up to `LineSum::CONTEXT` lines on either side, so an edit only
+//! recomputes the lines it touched and their context. Everything else about
+//! the document (line offsets, the running sum) is updated in place.
+//!
+//! Day 2 games may span lines, so `day_2::IncrementalGames` keeps games rather
+//! than lines; both are a `Document`.
+
+use std::fmt;
+use std::ops::Range;
+
+use crate::num::{lift_u128, Accumulator, Overflow};
+
+/// A puzzle part whose answer is a sum over the lines of its input.
+pub trait LineSum {
+    /// How many lines on each side a line's value depends on.
+    const CONTEXT: usize;
+    /// Whether every line's value also depends on the first line, so that
+    /// editing the first line recomputes all of them.
+    const ANCHORED: bool = false;
+    type Error: Into<Box<dyn std::error::Error>>;
+
+    /// The value of line `index`, or an error if the full solver fails. The
+    /// first line with an error gives the full solver's error, which may be
+    /// about a line next to it.
+    fn value(&self, lines: &Lines, index: usize) -> Result<u128, Self::Error>;
+
+    /// The error for a document without any lines, if that is one.
+    fn empty(&self) -> Option<Self::Error> {
+        None
+    }
+
+    /// An error about the whole document that the full solver reports before
+    /// any line's own error. Only asked for when some line has an error.
+    fn document_error(&self, _lines: &Lines) -> Option<Self::Error> {
+        None
+    }
+}
+
+/// The lines of a document, as `str::lines` splits them.
+#[derive(Clone, Copy)]
+pub struct Lines<'a> {
+    text: &'a str,
+    /// Where each line starts, including an empty last one after a final
+    /// newline, which `str::lines` does not count.
+    starts: &'a [usize],
+}
+
+impl<'a> Lines<'a> {
+    pub fn len(&self) -> usize {
+        match self.starts.last() {
+            Some(&last) if last == self.text.len() => self.starts.len() - 1,
+            _ => self.starts.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The whole document.
+    pub fn text(&self) -> &'a str {
+        self.text
+    }
+
+    pub fn get(&self, index: usize) -> Option<&'a str> {
+        if index >= self.len() {
+            return None;
+        }
+        match self.starts.get(index + 1) {
+            Some(&next) => {
+                let line = &self.text[self.starts[index]..next - 1];
+                Some(line.strip_suffix('\r').unwrap_or(line))
+            }
+            None => Some(&self.text[self.starts[index]..]),
+        }
+    }
+}
+
+/// An edit whose range is out of bounds or splits a character.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidEdit {
+    pub range: Range<usize>,
+    pub len: usize,
+}
+
+impl fmt::Display for InvalidEdit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Cannot replace bytes {}..{} of a {} byte document",
+            self.range.start, self.range.end, self.len
+        )
+    }
+}
+
+impl std::error::Error for InvalidEdit {}
+
+impl InvalidEdit {
+    /// Whether `range` can be replaced in `text`.
+    pub fn check(text: &str, range: &Range<usize>) -> Result<(), InvalidEdit> {
+        let valid = range.start <= range.end
+            && text.is_char_boundary(range.start)
+            && text.is_char_boundary(range.end);
+        if !valid {
+            let range = range.clone();
+            let len = text.len();
+            return Err(InvalidEdit { range, len });
+        }
+        Ok(())
+    }
+}
+
+/// A document that keeps its answers up to date while it is edited, by
+/// recomputing only the records (lines, games) an edit may have changed.
+pub trait Document {
+    fn text(&self) -> &str;
+
+    /// How many records the document holds.
+    fn records(&self) -> usize;
+
+    /// How many records the last edit, or creating the document, recomputed.
+    fn recomputed(&self) -> usize;
+
+    /// Replaces the bytes in `range` with `replacement`, then recomputes the
+    /// records that may have changed.
+    fn edit(&mut self, range: Range<usize>, replacement: &str) -> Result<(), InvalidEdit>;
+}
+
+/// A document with the answer of `P` over it.
+pub struct Incremental<P: LineSum> {
+    puzzle: P,
+    text: String,
+    starts: Vec<usize>,
+    /// The value of every line in `starts`, `None` for errors.
+    values: Vec<Option<u128>>,
+    sum: u128,
+    /// How many times `sum` has wrapped around, net of wrapping back.
+    carries: i64,
+    failures: usize,
+    recomputed: usize,
+}
+
+impl<P: LineSum> Incremental<P> {
+    pub fn new(puzzle: P, text: String) -> Self {
+        let mut starts = vec![0];
+        starts.extend(text.match_indices('\n').map(|(i, _)| i + 1));
+        let mut incremental = Incremental {
+            puzzle,
+            text,
+            values: vec![Some(0); starts.len()],
+            starts,
+            sum: 0,
+            carries: 0,
+            failures: 0,
+            recomputed: 0,
+        };
+        incremental.recompute(0..incremental.starts.len());
+        incremental
+    }
+
+    pub fn lines(&self) -> Lines<'_> {
+        Lines {
+            text: &self.text,
+            starts: &self.starts,
+        }
+    }
+
+    fn forget(&mut self, value: Option<u128>) {
+        match value {
+            Some(value) => {
+                let (sum, wrapped) = self.sum.overflowing_sub(value);
+                self.sum = sum;
+                self.carries -= wrapped as i64;
+            }
+            None => self.failures -= 1,
+        }
+    }
+
+    fn recompute(&mut self, indices: Range<usize>) {
+        self.recomputed = indices.len();
+        for index in indices {
+            let lines = self.lines();
+            let value = match lines.get(index) {
+                Some(_) => self.puzzle.value(&lines, index).ok(),
+                None => Some(0),
+            };
+            let old = std::mem::replace(&mut self.values[index], value);
+            self.forget(old);
+            match value {
+                Some(value) => {
+                    let (sum, wrapped) = self.sum.overflowing_add(value);
+                    self.sum = sum;
+                    self.carries += wrapped as i64;
+                }
+                None => self.failures += 1,
+            }
+        }
+    }
+
+    /// The answer over the current text, or the error of the whole document,
+    /// if any, or else of its first failing line.
+    pub fn answer<A: Accumulator>(&self) -> Result<A, Box<dyn std::error::Error>> {
+        let lines = self.lines();
+        if lines.is_empty() {
+            if let Some(error) = self.puzzle.empty() {
+                return Err(error.into());
+            }
+        }
+        if self.failures > 0 {
+            if let Some(error) = self.puzzle.document_error(&lines) {
+                return Err(error.into());
+            }
+            let index = self.values.iter().position(Option::is_none);
+            // Errors are found again, so that they carry current line numbers
+            if let Some(Err(error)) = index.map(|index| self.puzzle.value(&lines, index)) {
+                return Err(error.into());
+            }
+        }
+        if self.carries != 0 {
+            return Err(Overflow.into());
+        }
+        Ok(lift_u128(self.sum)?)
+    }
+}
+
+impl<P: LineSum> Document for Incremental<P> {
+    fn text(&self) -> &str {
+        &self.text
+    }
+
+    fn records(&self) -> usize {
+        self.lines().len()
+    }
+
+    fn recomputed(&self) -> usize {
+        self.recomputed
+    }
+
+    /// Recomputes the lines that now cover the edited part of the text, and
+    /// their context.
+    fn edit(&mut self, range: Range<usize>, replacement: &str) -> Result<(), InvalidEdit> {
+        InvalidEdit::check(&self.text, &range)?;
+
+        // Lines `first..=last` contain the range; they become the lines of the
+        // same stretch of the edited text
+        let first = self.starts.partition_point(|&start| start <= range.start) - 1;
+        let last = self.starts.partition_point(|&start| start <= range.end) - 1;
+        let old_end = self
+            .starts
+            .get(last + 1)
+            .map_or(self.text.len(), |&next| next - 1);
+        self.text.replace_range(range.clone(), replacement);
+        let new_end = old_end - range.len() + replacement.len();
+        let stretch_start = self.starts[first];
+        let new_starts: Vec<usize> = std::iter::once(stretch_start)
+            .chain(
+                self.text[stretch_start..new_end]
+                    .match_indices('\n')
+                    .map(|(i, _)| stretch_start + i + 1),
+            )
+            .collect();
+        let added = new_starts.len();
+        self.starts.splice(first..=last, new_starts);
+        for start in &mut self.starts[first + added..] {
+            *start = *start + replacement.len() - range.len();
+        }
+
+        let removed: Vec<Option<u128>> = self
+            .values
+            .splice(first..=last, std::iter::repeat_n(Some(0), added))
+            .collect();
+        for value in removed {
+            self.forget(value);
+        }
+        let dirty = if P::ANCHORED && first == 0 {
+            0..self.starts.len()
+        } else {
+            let end = (first + added + P::CONTEXT).min(self.starts.len());
+            first.saturating_sub(P::CONTEXT)..end
+        };
+        self.recompute(dirty);
+        Ok(())
+    }
+}
@@ -0,0 +1,13 @@
+extern crate self as steinerkelvin_aoc_2023;
+
+pub mod check;
+pub mod cli;
+pub mod day_1;
+pub mod day_2;
+pub mod day_3;
+pub mod incremental;
+pub mod num;
+pub mod parser;
+pub mod reference;
+pub mod registry;
+pub mod rng;
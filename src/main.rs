@@ -1,5 +1,7 @@
 use std::process::Command;
 
+const USAGE: &str = "usage: steinerkelvin-aoc-2023 [repl 1|2|3 [--input PATH]]";
+
 fn run_bin(name: &str) {
     let output = Command::new("cargo")
         .arg("run")
@@ -11,10 +13,30 @@ fn run_bin(name: &str) {
     println!("{}", String::from_utf8_lossy(&output.stdout));
 }
 
-fn main() {
+/// Hands the terminal to the `repl` binary, with the arguments after `repl`.
+fn repl(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let status = Command::new("cargo")
+        .args(["run", "--quiet", "--bin", "repl", "--"])
+        .args(args)
+        .status()?;
+    if !status.success() {
+        return Err(format!("repl exited with {}", status).into());
+    }
+    Ok(())
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.split_first() {
+        Some((command, rest)) if command == "repl" => return repl(rest),
+        Some(_) => return Err(USAGE.into()),
+        None => {}
+    }
+
     let bins = ["day_1_a", "day_1_b", "day_2_a", "day_2_b", "day_3_a"];
     for bin in &bins {
         println!("Running {}", bin);
         run_bin(bin);
     }
+    Ok(())
 }
@@ -0,0 +1,181 @@
+//! Integer types that puzzle answers are accumulated in, with overflow checked
+//! instead of wrapping.
+
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Overflow;
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Arithmetic overflow, try a wider --int")
+    }
+}
+
+impl std::error::Error for Overflow {}
+
+pub trait Accumulator: Sized + Clone + fmt::Display {
+    fn from_u64(n: u64) -> Option<Self>;
+    fn checked_add(&self, rhs: &Self) -> Option<Self>;
+    fn checked_mul(&self, rhs: &Self) -> Option<Self>;
+}
+
+macro_rules! impl_accumulator {
+    ($($int:ty),*) => {$(
+        impl Accumulator for $int {
+            fn from_u64(n: u64) -> Option<Self> {
+                n.try_into().ok()
+            }
+            fn checked_add(&self, rhs: &Self) -> Option<Self> {
+                <$int>::checked_add(*self, *rhs)
+            }
+            fn checked_mul(&self, rhs: &Self) -> Option<Self> {
+                <$int>::checked_mul(*self, *rhs)
+            }
+        }
+    )*};
+}
+
+impl_accumulator!(u32, u64, u128);
+
+pub fn lift<A: Accumulator>(n: u64) -> Result<A, Overflow> {
+    A::from_u64(n).ok_or(Overflow)
+}
+
+/// Like `lift`, for values that may need up to 128 bits.
+pub fn lift_u128<A: Accumulator>(n: u128) -> Result<A, Overflow> {
+    if let Ok(n) = u64::try_from(n) {
+        return lift(n);
+    }
+    let shift = lift::<A>(1 << 32)?;
+    let high = lift::<A>((n >> 64) as u64)?;
+    let high = high
+        .checked_mul(&shift)
+        .and_then(|high| high.checked_mul(&shift));
+    let low = lift::<A>(n as u64)?;
+    high.and_then(|high| high.checked_add(&low)).ok_or(Overflow)
+}
+
+pub fn lift_all<A: Accumulator>(values: impl IntoIterator<Item = u64>) -> Result<Vec<A>, Overflow> {
+    values.into_iter().map(lift).collect()
+}
+
+pub fn checked_sum<A: Accumulator>(values: impl IntoIterator<Item = A>) -> Result<A, Overflow> {
+    let mut sum = lift::<A>(0)?;
+    for value in values {
+        sum = sum.checked_add(&value).ok_or(Overflow)?;
+    }
+    Ok(sum)
+}
+
+pub fn checked_product<A: Accumulator>(values: impl IntoIterator<Item = A>) -> Result<A, Overflow> {
+    let mut product = lift::<A>(1)?;
+    for value in values {
+        product = product.checked_mul(&value).ok_or(Overflow)?;
+    }
+    Ok(product)
+}
+
+/// Arbitrary-precision unsigned integer, as little-endian base 2^32 limbs with
+/// no trailing zero limbs. It never overflows.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BigUint(Vec<u32>);
+
+impl BigUint {
+    fn normalized(mut limbs: Vec<u32>) -> Self {
+        while limbs.last() == Some(&0) {
+            limbs.pop();
+        }
+        BigUint(limbs)
+    }
+
+    /// Divides in place by a single limb, returning the remainder.
+    fn div_rem_small(&mut self, divisor: u32) -> u32 {
+        let mut rem = 0u64;
+        for limb in self.0.iter_mut().rev() {
+            let cur = (rem << 32) | *limb as u64;
+            *limb = (cur / divisor as u64) as u32;
+            rem = cur % divisor as u64;
+        }
+        *self = BigUint::normalized(std::mem::take(&mut self.0));
+        rem as u32
+    }
+}
+
+impl Accumulator for BigUint {
+    fn from_u64(n: u64) -> Option<Self> {
+        Some(BigUint::normalized(vec![n as u32, (n >> 32) as u32]))
+    }
+
+    fn checked_add(&self, rhs: &Self) -> Option<Self> {
+        let len = self.0.len().max(rhs.0.len());
+        let mut limbs = Vec::with_capacity(len + 1);
+        let mut carry = 0u64;
+        for i in 0..len {
+            let a = self.0.get(i).copied().unwrap_or(0) as u64;
+            let b = rhs.0.get(i).copied().unwrap_or(0) as u64;
+            let cur = a + b + carry;
+            limbs.push(cur as u32);
+            carry = cur >> 32;
+        }
+        limbs.push(carry as u32);
+        Some(BigUint::normalized(limbs))
+    }
+
+    fn checked_mul(&self, rhs: &Self) -> Option<Self> {
+        let mut limbs = vec![0u32; self.0.len() + rhs.0.len()];
+        for (i, &a) in self.0.iter().enumerate() {
+            let mut carry = 0u64;
+            for (j, &b) in rhs.0.iter().enumerate() {
+                let cur = limbs[i + j] as u64 + a as u64 * b as u64 + carry;
+                limbs[i + j] = cur as u32;
+                carry = cur >> 32;
+            }
+            limbs[i + rhs.0.len()] = carry as u32;
+        }
+        Some(BigUint::normalized(limbs))
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.0.is_empty() {
+            return f.pad("0");
+        }
+        let mut n = self.clone();
+        let mut chunks = Vec::new();
+        while !n.0.is_empty() {
+            chunks.push(n.div_rem_small(1_000_000_000));
+        }
+        let mut digits = chunks.pop().unwrap_or(0).to_string();
+        for chunk in chunks.iter().rev() {
+            digits.push_str(&format!("{:09}", chunk));
+        }
+        f.pad(&digits)
+    }
+}
+
+/// Calls the generic solver `$solve` with the accumulator named by `$int`
+/// (`u32`, `u64`, `u128` or `big`) and gives back its answer as a string, as
+/// `Result<String, Box<dyn std::error::Error>>`.
+#[macro_export]
+macro_rules! with_accumulator {
+    ($int:expr, $solve:ident($($arg:expr),*)) => {{
+        fn show<A: std::fmt::Display, E: Into<Box<dyn std::error::Error>>>(
+            result: Result<A, E>,
+        ) -> Result<String, Box<dyn std::error::Error>> {
+            result.map(|n| n.to_string()).map_err(Into::into)
+        }
+        match $int {
+            "u32" => show($solve::<u32>($($arg),*)),
+            "u64" => show($solve::<u64>($($arg),*)),
+            "u128" => show($solve::<u128>($($arg),*)),
+            "big" => show($solve::<$crate::num::BigUint>($($arg),*)),
+            other => Err(format!(
+                "Unknown integer type {:?}, expected u32, u64, u128 or big",
+                other
+            )
+            .into()),
+        }
+    }};
+}
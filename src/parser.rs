@@ -0,0 +1,395 @@
+//! Minimal parser combinators over a borrowed input string.
+
+use std::cell::{Cell, RefCell};
+use std::fmt;
+
+#[derive(Clone, Copy)]
+pub struct Parser<'a> {
+    pub body: &'a str,
+    pub pos: usize,
+}
+
+impl Parser<'_> {
+    pub fn as_str(&self) -> &str {
+        &self.body[self.pos..]
+    }
+
+    /// 1-based line and column (in chars) of the cursor.
+    pub fn line_col(&self) -> (usize, usize) {
+        let done = &self.body[..self.pos];
+        let line_start = done.rfind('\n').map_or(0, |i| i + 1);
+        let line = done.matches('\n').count() + 1;
+        (line, done[line_start..].chars().count() + 1)
+    }
+}
+
+impl<'a> Parser<'a> {
+    pub fn at(self, pos: usize) -> Parser<'a> {
+        Parser {
+            body: self.body,
+            pos,
+        }
+    }
+    pub fn with<T>(self, item: T) -> ParserResult<'a, T> {
+        Some((self, item))
+    }
+}
+
+pub type ParserResult<'a, T> = Option<(Parser<'a>, T)>;
+
+// Tracing
+
+/// What named rules record while they run. Character-level helpers (`digit`,
+/// `letter`, `skip_whitespace`, `repeat_to_str`) are never traced, to keep the
+/// tree readable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trace {
+    Off,
+    /// Only remember the deepest failure, for `consume` and `deepest_failure`.
+    Failures,
+    /// Also print every rule's entry and exit to stderr as an indented tree.
+    Tree,
+}
+
+/// The furthest position any rule failed at during the last top-level parse,
+/// with the stack of rules that led there (outermost first).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Failure {
+    pub pos: usize,
+    pub line: usize,
+    pub col: usize,
+    pub stack: Vec<String>,
+    /// The start of the input that was not accepted.
+    pub rest: String,
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "deepest failure at line {}, column {}, before {:?}, in {}",
+            self.line,
+            self.col,
+            self.rest,
+            self.stack.join(" > ")
+        )
+    }
+}
+
+#[derive(Default)]
+struct TraceState {
+    stack: Vec<(String, usize)>,
+    deepest: Option<Failure>,
+}
+
+thread_local! {
+    static TRACE_MODE: Cell<Trace> = const { Cell::new(Trace::Off) };
+    static TRACE: RefCell<TraceState> = RefCell::new(TraceState::default());
+}
+
+/// Switches tracing for the current thread, forgetting any recorded failure.
+pub fn set_trace(mode: Trace) {
+    TRACE_MODE.with(|cell| cell.set(mode));
+    TRACE.with(|state| *state.borrow_mut() = TraceState::default());
+}
+
+pub fn trace_mode() -> Trace {
+    TRACE_MODE.with(Cell::get)
+}
+
+pub fn deepest_failure() -> Option<Failure> {
+    TRACE.with(|state| state.borrow().deepest.clone())
+}
+
+/// Up to 24 characters of `s`, for log lines.
+fn preview(s: &str) -> &str {
+    s.char_indices().nth(24).map_or(s, |(end, _)| &s[..end])
+}
+
+/// Runs `f` as the rule `name`, tracing it when tracing is on. `detail` is
+/// shown next to the name, like the text `exact` expects.
+pub fn rule<'a, T>(
+    name: &'static str,
+    detail: &str,
+    parser: Parser<'a>,
+    f: impl FnOnce(Parser<'a>) -> ParserResult<'a, T>,
+) -> ParserResult<'a, T> {
+    let mode = trace_mode();
+    if mode == Trace::Off {
+        return f(parser);
+    }
+    let label = if detail.is_empty() {
+        name.to_string()
+    } else {
+        format!("{} {:?}", name, detail)
+    };
+    TRACE.with(|state| {
+        let mut state = state.borrow_mut();
+        if state.stack.is_empty() {
+            state.deepest = None;
+        }
+        if mode == Trace::Tree {
+            let indent = "  ".repeat(state.stack.len());
+            eprintln!("{}{} at {}", indent, label, parser.pos);
+        }
+        state.stack.push((label, parser.pos));
+    });
+
+    let result = f(parser);
+
+    TRACE.with(|state| {
+        let mut state = state.borrow_mut();
+        let Some((label, start)) = state.stack.pop() else {
+            return;
+        };
+        let indent = "  ".repeat(state.stack.len());
+        match &result {
+            Some((end, _)) if mode == Trace::Tree => {
+                let consumed = &parser.body[start..end.pos];
+                eprintln!("{}{} ok {:?}", indent, label, preview(consumed));
+            }
+            Some(_) => {}
+            None => {
+                if mode == Trace::Tree {
+                    eprintln!("{}{} failed", indent, label);
+                }
+                if state.deepest.as_ref().is_some_and(|d| d.pos >= start) {
+                    return;
+                }
+                let (line, col) = parser.line_col();
+                let mut stack: Vec<String> = state.stack.iter().map(|(l, _)| l.clone()).collect();
+                stack.push(label);
+                state.deepest = Some(Failure {
+                    pos: start,
+                    line,
+                    col,
+                    stack,
+                    rest: preview(parser.as_str()).to_string(),
+                });
+            }
+        }
+    });
+    result
+}
+
+// Combinators
+
+pub fn exact<'a>(parser: Parser<'a>, s: &str) -> ParserResult<'a, ()> {
+    rule("exact", s, parser, |parser| {
+        if !parser.as_str().starts_with(s) {
+            return None;
+        }
+        parser.at(parser.pos + s.len()).with(())
+    })
+}
+
+pub fn skip_whitespace(parser: Parser) -> ParserResult<()> {
+    let body = parser.body;
+    let mut end = parser.pos;
+    while let Some(c) = body[end..].chars().next() {
+        if !c.is_whitespace() {
+            break;
+        }
+        end += c.len_utf8();
+    }
+    parser.at(end).with(())
+}
+
+/// Like `skip_whitespace`, but fails unless there is at least one whitespace
+/// character to skip.
+pub fn require_whitespace(parser: Parser) -> ParserResult<()> {
+    rule("require_whitespace", "", parser, |parser| {
+        let (next, _) = skip_whitespace(parser)?;
+        if next.pos == parser.pos {
+            return None;
+        }
+        next.with(())
+    })
+}
+
+pub fn digit(parser: Parser) -> ParserResult<char> {
+    let c = parser.as_str();
+    if let Some(c) = c.chars().next() {
+        if c.is_ascii_digit() {
+            return parser.at(parser.pos + 1).with(c);
+        }
+    }
+    None
+}
+
+pub fn letter(parser: Parser) -> ParserResult<char> {
+    let c = parser.as_str();
+    if let Some(c) = c.chars().next() {
+        if c.is_alphabetic() || c == '_' {
+            return parser.at(parser.pos + c.len_utf8()).with(c);
+        }
+    }
+    None
+}
+
+pub fn repeat_to_str<'a, T>(
+    parser: Parser<'a>,
+    f: fn(Parser<'a>) -> ParserResult<'a, T>,
+) -> ParserResult<'a, &'a str> {
+    let start = parser;
+    let mut cur_parser = parser;
+    while let Some((parser, _)) = f(cur_parser) {
+        cur_parser = parser;
+    }
+    let end = cur_parser.pos;
+    cur_parser.with(&start.body[start.pos..end])
+}
+
+pub fn parse_u32(parser: Parser) -> ParserResult<u32> {
+    rule("parse_u32", "", parser, |parser| {
+        let (parser, digits) = repeat_to_str(parser, digit)?;
+        let n = digits.parse::<u32>().ok()?;
+        parser.with(n)
+    })
+}
+
+pub fn parse_u64(parser: Parser) -> ParserResult<u64> {
+    rule("parse_u64", "", parser, |parser| {
+        let (parser, digits) = repeat_to_str(parser, digit)?;
+        let n = digits.parse::<u64>().ok()?;
+        parser.with(n)
+    })
+}
+
+pub fn parse_list<'a, T>(
+    parser: Parser<'a>,
+    sep: &'static str,
+    item_parser: fn(Parser<'a>) -> ParserResult<'a, T>,
+) -> ParserResult<'a, Vec<T>> {
+    rule("parse_list", sep, parser, |parser| {
+        parse_list_untraced(parser, sep, item_parser)
+    })
+}
+
+fn parse_list_untraced<'a, T>(
+    parser: Parser<'a>,
+    sep: &str,
+    item_parser: fn(Parser<'a>) -> ParserResult<'a, T>,
+) -> ParserResult<'a, Vec<T>> {
+    let mut nums: Vec<T> = Vec::new();
+    let mut cur_parser = parser;
+    loop {
+        let (parser, num) = item_parser(cur_parser)?;
+        nums.push(num);
+        cur_parser = parser;
+        let (parser, _) = skip_whitespace(cur_parser)?;
+        if let Some((parser, _)) = exact(parser, sep) {
+            let (parser, _) = skip_whitespace(parser)?;
+            cur_parser = parser;
+        } else {
+            break;
+        }
+    }
+    cur_parser.with(nums)
+}
+
+// Parsing by type
+
+/// Types with one canonical textual form. Usually derived: `#[derive(FromInput)]`
+/// with a `#[format("...")]` attribute, see `steinerkelvin_aoc_2023_derive`.
+pub trait FromInput: Sized {
+    fn from_input(parser: Parser) -> ParserResult<Self>;
+}
+
+pub use steinerkelvin_aoc_2023_derive::FromInput;
+
+impl FromInput for u32 {
+    fn from_input(parser: Parser) -> ParserResult<Self> {
+        parse_u32(parser)
+    }
+}
+
+impl FromInput for u64 {
+    fn from_input(parser: Parser) -> ParserResult<Self> {
+        parse_u64(parser)
+    }
+}
+
+/// Unwraps a parse of the whole input, panicking if it failed or left input
+/// over. With tracing on, the panic names the deepest failure.
+pub fn consume<T>(parser_result: ParserResult<T>) -> T {
+    let deepest = deepest_failure().map_or(String::new(), |failure| format!("; {}", failure));
+    let Some((parser, result)) = parser_result else {
+        panic!("Parser failed{}", deepest)
+    };
+    if parser.as_str().is_empty() {
+        result
+    } else {
+        let tail = parser.as_str();
+        panic!(
+            "Parser did not consume all input, remaining:\n{:?}{}",
+            tail, deepest
+        )
+    }
+}
+
+/// Declares parsers for fixed record formats as a sequence of steps ending in
+/// `=> expression`, which builds the result from the fields bound on the way:
+///
+/// ```ignore
+/// grammar! {
+///     pub fn parse_color_qty -> ColorQty {
+///         ws qty = u32 ws color = parse_color => ColorQty { color, qty }
+///     }
+/// }
+/// ```
+///
+/// The steps are:
+///
+/// - `"text"` matches exactly that text
+/// - `ws` skips whitespace
+/// - `field = u32` or `field = parse_x` binds the result of a parser
+/// - `field = list(",", parse_x)` binds a separated list; the item can also be
+///   an inline `{ steps => expression }`
+///
+/// Each generated function runs as a named `rule`, so it shows up in traces
+/// and failure reports like a hand-written one.
+#[macro_export]
+macro_rules! grammar {
+    ($($(#[$meta:meta])* $vis:vis fn $name:ident -> $out:ty { $($steps:tt)* })*) => {$(
+        $(#[$meta])*
+        $vis fn $name(parser: $crate::parser::Parser) -> $crate::parser::ParserResult<$out> {
+            $crate::parser::rule(stringify!($name), "", parser, |parser| {
+                $crate::grammar!(@steps parser; $($steps)*)
+            })
+        }
+    )*};
+
+    (@steps $p:ident; => $result:expr) => {
+        $p.with($result)
+    };
+    (@steps $p:ident; ws $($rest:tt)*) => {{
+        let ($p, _) = $crate::parser::skip_whitespace($p)?;
+        $crate::grammar!(@steps $p; $($rest)*)
+    }};
+    (@steps $p:ident; $text:literal $($rest:tt)*) => {{
+        let ($p, _) = $crate::parser::exact($p, $text)?;
+        $crate::grammar!(@steps $p; $($rest)*)
+    }};
+    (@steps $p:ident; $field:ident = list($sep:literal, { $($item:tt)* }) $($rest:tt)*) => {{
+        let ($p, $field) = $crate::parser::parse_list($p, $sep, |$p| {
+            $crate::grammar!(@steps $p; $($item)*)
+        })?;
+        $crate::grammar!(@steps $p; $($rest)*)
+    }};
+    (@steps $p:ident; $field:ident = list($sep:literal, $item:ident) $($rest:tt)*) => {{
+        let ($p, $field) = $crate::parser::parse_list($p, $sep, $crate::grammar!(@fn $item))?;
+        $crate::grammar!(@steps $p; $($rest)*)
+    }};
+    (@steps $p:ident; $field:ident = $item:ident $($rest:tt)*) => {{
+        let ($p, $field) = $crate::grammar!(@fn $item)($p)?;
+        $crate::grammar!(@steps $p; $($rest)*)
+    }};
+
+    (@fn u32) => {
+        $crate::parser::parse_u32
+    };
+    (@fn $item:ident) => {
+        $item
+    };
+}
@@ -0,0 +1,260 @@
+//! Deliberately naive reference solvers for every part, written to be obviously
+//! right rather than fast, so that the real solvers can be checked against them.
+//! They share no code with the real solvers, not even tables. Each one gives
+//! `None` where the real solver gives an error.
+
+// Day 1
+
+const DIGITS: &[(&str, u32)] = &[
+    ("0", 0),
+    ("1", 1),
+    ("2", 2),
+    ("3", 3),
+    ("4", 4),
+    ("5", 5),
+    ("6", 6),
+    ("7", 7),
+    ("8", 8),
+    ("9", 9),
+];
+
+const WORDS: &[(&str, u32)] = &[
+    ("zero", 0),
+    ("one", 1),
+    ("two", 2),
+    ("three", 3),
+    ("four", 4),
+    ("five", 5),
+    ("six", 6),
+    ("seven", 7),
+    ("eight", 8),
+    ("nine", 9),
+];
+
+fn calibration_value(line: &str, digits: &[(&str, u32)]) -> Option<u128> {
+    let mut found = Vec::new();
+    for start in 0..line.len() {
+        for end in start + 1..=line.len() {
+            let Some(substring) = line.get(start..end) else {
+                continue;
+            };
+            for &(word, digit) in digits {
+                if substring == word {
+                    found.push((start, digit));
+                }
+            }
+        }
+    }
+    let first = found.iter().min_by_key(|(start, _)| *start)?.1;
+    let last = found.iter().max_by_key(|(start, _)| *start)?.1;
+    Some((first * 10 + last) as u128)
+}
+
+pub fn day_1_part_1(body: &str) -> Option<u128> {
+    body.lines()
+        .map(|line| calibration_value(line, DIGITS))
+        .sum()
+}
+
+/// Checks every substring of every line against every digit and word.
+pub fn day_1_part_2(body: &str) -> Option<u128> {
+    let numbers = [DIGITS, WORDS].concat();
+    body.lines()
+        .map(|line| calibration_value(line, &numbers))
+        .sum()
+}
+
+// Day 2
+
+type Draw<'a> = (&'a str, u32);
+
+/// Splits `Game 1: 3 blue, 4 red; 2 green` into its id and every draw. There
+/// must be whitespace after `Game` and after each quantity, and may be any
+/// elsewhere, except before the colon.
+fn parse_game(line: &str) -> Option<(u32, Vec<Draw<'_>>)> {
+    let (head, sets) = line.split_once(':')?;
+    let id = head.strip_prefix("Game")?;
+    if !id.starts_with(char::is_whitespace) {
+        return None;
+    }
+    let id = id.trim_start();
+    if !id.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let id = id.parse().ok()?;
+    let mut draws = Vec::new();
+    for set in sets.split(';') {
+        for draw in set.split(',') {
+            let (qty, color) = draw.trim().split_once(char::is_whitespace)?;
+            let color = color.trim_start();
+            if !qty.bytes().all(|b| b.is_ascii_digit())
+                || color.is_empty()
+                || !color.chars().all(|c| c.is_alphabetic() || c == '_')
+            {
+                return None;
+            }
+            draws.push((color, qty.parse().ok()?));
+        }
+    }
+    Some((id, draws))
+}
+
+fn parse_games(body: &str) -> Option<Vec<(u32, Vec<Draw<'_>>)>> {
+    body.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(parse_game)
+        .collect()
+}
+
+/// Sums the ids of the games whose every draw fits in `bag`.
+pub fn day_2_part_1(body: &str, bag: &[Draw]) -> Option<u128> {
+    let games = parse_games(body)?;
+    let fits = |&(color, qty): &Draw| {
+        let max = bag
+            .iter()
+            .find(|(name, _)| *name == color)
+            .map_or(0, |d| d.1);
+        qty <= max
+    };
+    let possible = games.iter().filter(|(_, draws)| draws.iter().all(fits));
+    Some(possible.map(|(id, _)| *id as u128).sum())
+}
+
+/// Sums, over each game, the product of its largest draw of every colour
+/// drawn anywhere in the document.
+pub fn day_2_part_2(body: &str) -> Option<u128> {
+    let games = parse_games(body)?;
+    let mut colors: Vec<&str> = Vec::new();
+    for (_, draws) in &games {
+        for &(color, _) in draws {
+            if !colors.contains(&color) {
+                colors.push(color);
+            }
+        }
+    }
+    let power = |draws: &[Draw]| -> u128 {
+        colors
+            .iter()
+            .map(|&color| {
+                draws
+                    .iter()
+                    .filter(|(name, _)| *name == color)
+                    .map(|(_, qty)| *qty as u128)
+                    .max()
+                    .unwrap_or(0)
+            })
+            .product()
+    };
+    Some(games.iter().map(|(_, draws)| power(draws)).sum())
+}
+
+// Day 3
+
+struct Number {
+    value: u128,
+    row: usize,
+    cols: std::ops::Range<usize>,
+}
+
+fn is_symbol(byte: u8) -> bool {
+    byte != b'.' && !byte.is_ascii_digit()
+}
+
+/// The grid's lines and numbers, or `None` if it is empty, ragged or not ASCII.
+fn scan_grid(input: &str) -> Option<(Vec<&[u8]>, Vec<Number>)> {
+    let mut grid: Vec<&[u8]> = input
+        .lines()
+        .map(|line| line.strip_suffix('\r').unwrap_or(line).as_bytes())
+        .collect();
+    while grid.last().is_some_and(|line| line.is_empty()) {
+        grid.pop();
+    }
+    if grid.is_empty()
+        || grid
+            .iter()
+            .any(|line| line.len() != grid[0].len() || !line.is_ascii())
+    {
+        return None;
+    }
+    let mut numbers = Vec::new();
+    for (row, line) in grid.iter().enumerate() {
+        let mut col = 0;
+        while col < line.len() {
+            let end = col
+                + line[col..]
+                    .iter()
+                    .take_while(|b| b.is_ascii_digit())
+                    .count();
+            if end > col {
+                let digits = std::str::from_utf8(&line[col..end]).ok()?;
+                let value: u128 = digits.parse().ok()?;
+                if value > u64::MAX as u128 {
+                    return None;
+                }
+                numbers.push(Number {
+                    value,
+                    row,
+                    cols: col..end,
+                });
+                col = end;
+            } else {
+                col += 1;
+            }
+        }
+    }
+    Some((grid, numbers))
+}
+
+/// The eight neighbours of a cell that lie inside the grid.
+fn neighbours(grid: &[&[u8]], row: usize, col: usize) -> Vec<(usize, usize)> {
+    let mut cells = Vec::new();
+    for y in row.saturating_sub(1)..=row + 1 {
+        for x in col.saturating_sub(1)..=col + 1 {
+            if (y, x) != (row, col) && y < grid.len() && x < grid[y].len() {
+                cells.push((y, x));
+            }
+        }
+    }
+    cells
+}
+
+/// Looks at all eight neighbours of every digit for a symbol.
+pub fn day_3_part_1(input: &str) -> Option<u128> {
+    let (grid, numbers) = scan_grid(input)?;
+    let is_part = |number: &Number| {
+        number.cols.clone().any(|col| {
+            neighbours(&grid, number.row, col)
+                .into_iter()
+                .any(|(y, x)| is_symbol(grid[y][x]))
+        })
+    };
+    Some(numbers.iter().filter(|n| is_part(n)).map(|n| n.value).sum())
+}
+
+/// Multiplies the numbers around every `*` that touches exactly two of them.
+pub fn day_3_part_2(input: &str) -> Option<u128> {
+    let (grid, numbers) = scan_grid(input)?;
+    let mut sum = 0;
+    for (row, line) in grid.iter().enumerate() {
+        for (col, &byte) in line.iter().enumerate() {
+            if byte != b'*' {
+                continue;
+            }
+            let cells = neighbours(&grid, row, col);
+            let touching: Vec<u128> = numbers
+                .iter()
+                .filter(|number| {
+                    number
+                        .cols
+                        .clone()
+                        .any(|x| cells.contains(&(number.row, x)))
+                })
+                .map(|number| number.value)
+                .collect();
+            if let [a, b] = touching[..] {
+                sum += a.checked_mul(b)?;
+            }
+        }
+    }
+    Some(sum)
+}
@@ -0,0 +1,201 @@
+//! Every day of the season in one table, so that tools can list, run and check
+//! them without knowing each day's API. A day brings its bundled input, a
+//! solver per part with the answer the site accepted, the examples from the
+//! puzzle text, and any views of its input.
+
+use std::error::Error;
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::day_1::{self, day_1_trebuchet, day_1_trebuchet_part_2};
+use crate::day_2::{self, bag_from_str, day_2_cube_conundrum, parse_input};
+use crate::day_2::{day_2_cube_conundrum_part_2, DEFAULT_BAG};
+use crate::day_3::{self, day_3_gear_ratios, day_3_gear_ratios_part_2, GearRule, Schematic};
+
+pub type Solve = fn(&str) -> Result<String, Box<dyn Error>>;
+
+pub struct Part {
+    pub solve: Solve,
+    /// The answer accepted for the bundled input, once known.
+    pub answer: Option<&'static str>,
+}
+
+/// An example from the puzzle text, with the answer it gives in one part.
+pub struct Example {
+    pub part: usize,
+    pub input: &'static str,
+    pub answer: &'static str,
+}
+
+/// A rendering of a day's input, for looking at rather than parsing.
+pub struct View {
+    pub name: &'static str,
+    pub render: fn(&str) -> Result<String, Box<dyn Error>>,
+}
+
+pub struct Day {
+    pub number: u32,
+    pub title: &'static str,
+    pub input: &'static str,
+    pub parts: [Part; 2],
+    pub examples: &'static [Example],
+    pub views: &'static [View],
+}
+
+pub const DAYS: &[Day] = &[
+    Day {
+        number: 1,
+        title: "Trebuchet?!",
+        input: include_str!("../input/day_1_a.txt"),
+        parts: [
+            Part {
+                solve: |body| Ok(day_1_trebuchet::<u128>(body)?.to_string()),
+                answer: Some("55607"),
+            },
+            Part {
+                solve: |body| Ok(day_1_trebuchet_part_2::<u128>(body)?.to_string()),
+                answer: Some("55291"),
+            },
+        ],
+        examples: &[
+            Example {
+                part: 1,
+                input: day_1::EXAMPLE,
+                answer: "142",
+            },
+            Example {
+                part: 2,
+                input: day_1::EXAMPLE_PART_2,
+                answer: "281",
+            },
+        ],
+        views: &[],
+    },
+    Day {
+        number: 2,
+        title: "Cube Conundrum",
+        input: include_str!("../input/day_2_a.txt"),
+        parts: [
+            Part {
+                solve: |body| {
+                    let bag = bag_from_str(DEFAULT_BAG).ok_or("Invalid default bag")?;
+                    Ok(day_2_cube_conundrum::<u128>(&parse_input(body)?, &bag)?.to_string())
+                },
+                answer: Some("2406"),
+            },
+            Part {
+                solve: |body| {
+                    Ok(day_2_cube_conundrum_part_2::<u128>(&parse_input(body)?)?.to_string())
+                },
+                answer: Some("78375"),
+            },
+        ],
+        examples: &[
+            Example {
+                part: 1,
+                input: day_2::EXAMPLE,
+                answer: "8",
+            },
+            Example {
+                part: 2,
+                input: day_2::EXAMPLE,
+                answer: "2286",
+            },
+        ],
+        views: &[],
+    },
+    Day {
+        number: 3,
+        title: "Gear Ratios",
+        input: include_str!("../input/day_3_a.txt"),
+        parts: [
+            Part {
+                solve: |input| Ok(day_3_gear_ratios::<u128>(input)?.to_string()),
+                answer: Some("559667"),
+            },
+            Part {
+                solve: |input| {
+                    Ok(day_3_gear_ratios_part_2::<u128>(input, &GearRule::puzzle())?.to_string())
+                },
+                answer: Some("86841457"),
+            },
+        ],
+        examples: &[
+            Example {
+                part: 1,
+                input: day_3::EXAMPLE,
+                answer: "4361",
+            },
+            Example {
+                part: 2,
+                input: day_3::EXAMPLE,
+                answer: "467835",
+            },
+        ],
+        views: &[
+            View {
+                name: "schematic",
+                render: |input| {
+                    Ok(Schematic::parse(input)?.render(input, &GearRule::puzzle(), true))
+                },
+            },
+            View {
+                name: "graph (DOT)",
+                render: |input| Ok(Schematic::parse(input)?.to_dot()),
+            },
+        ],
+    },
+];
+
+/// The outcome of running one part.
+#[derive(Debug, Clone)]
+pub struct Run {
+    pub answer: Result<String, String>,
+    pub elapsed: Duration,
+    pub at: SystemTime,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    /// Not run yet.
+    Unsolved,
+    /// Ran into an error, or an answer other than the accepted one.
+    Failed,
+    /// Gave an answer, with no accepted answer to compare it to.
+    Solved,
+    /// Gave the accepted answer.
+    Verified,
+}
+
+impl Part {
+    pub fn run(&self, input: &str) -> Run {
+        let at = SystemTime::now();
+        let start = Instant::now();
+        let answer = (self.solve)(input).map_err(|err| err.to_string());
+        Run {
+            answer,
+            elapsed: start.elapsed(),
+            at,
+        }
+    }
+
+    pub fn status(&self, run: Option<&Run>) -> Status {
+        match (run.map(|run| &run.answer), self.answer) {
+            (None, _) => Status::Unsolved,
+            (Some(Err(_)), _) => Status::Failed,
+            (Some(Ok(_)), None) => Status::Solved,
+            (Some(Ok(answer)), Some(accepted)) if answer == accepted => Status::Verified,
+            (Some(Ok(_)), Some(_)) => Status::Failed,
+        }
+    }
+}
+
+impl Example {
+    /// Runs the example through `day`, giving the answer when it is wrong.
+    pub fn check(&self, day: &Day) -> Result<(), String> {
+        match (day.parts[self.part - 1].solve)(self.input) {
+            Ok(answer) if answer == self.answer => Ok(()),
+            Ok(answer) => Err(format!("expected {}, got {}", self.answer, answer)),
+            Err(err) => Err(format!("expected {}, got error: {}", self.answer, err)),
+        }
+    }
+}
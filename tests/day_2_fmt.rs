@@ -0,0 +1,106 @@
+//! Printing Day 2 games and parsing them back, on unusual colour names and
+//! whitespace, in the library and through `day_2_fmt`, and where whitespace may
+//! be left out.
+
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+use steinerkelvin_aoc_2023::day_2::{parse_input, Games};
+use steinerkelvin_aoc_2023::reference;
+
+const UNUSUAL: &[&str] = &[
+    "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green",
+    "Game 2: 3 dark_red,4 _;1 Rouge",
+    "Game 3:\t 7 bleu\u{e9} ,  1 \u{7ea2}\u{8272} ;\n 2   verde",
+    "Game 4: 0 a; 4294967295 ZZ_z",
+    "Game 5:   1\tred  \r\n",
+    "Game 6: 1 red\r\nGame 7: 2 red\n\nGame 8: 3 red",
+];
+
+#[test]
+fn printed_games_parse_back_equal() {
+    for &body in UNUSUAL {
+        let games = parse_input(body).unwrap_or_else(|err| panic!("{:?}: {}", body, err));
+        assert!(!games.is_empty(), "{:?} has no games", body);
+        for game in games {
+            let printed = game.to_string();
+            let reparsed = parse_input(&printed)
+                .unwrap_or_else(|err| panic!("{:?} does not parse back: {}", printed, err));
+            assert_eq!(reparsed, [game], "{:?} from {:?}", printed, body);
+        }
+    }
+}
+
+fn day_2_fmt(body: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_day_2_fmt"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("day_2_fmt runs");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(body.as_bytes())
+        .unwrap();
+    child.wait_with_output().expect("day_2_fmt finishes")
+}
+
+#[test]
+fn day_2_fmt_output_is_canonical() {
+    for &body in UNUSUAL {
+        let first = day_2_fmt(body);
+        assert!(
+            first.status.success(),
+            "day_2_fmt failed on {:?}: {}",
+            body,
+            String::from_utf8_lossy(&first.stderr)
+        );
+        let printed = String::from_utf8(first.stdout).unwrap();
+        let second = day_2_fmt(&printed);
+        assert!(second.status.success(), "{:?} does not format", printed);
+        assert_eq!(String::from_utf8(second.stdout).unwrap(), printed);
+    }
+}
+
+#[test]
+fn day_2_fmt_reports_malformed_input() {
+    for body in ["Game 1: 3 blue,", "Game x: 1 red", "Game 1: 1 red 2"] {
+        let output = day_2_fmt(body);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(!output.status.success(), "day_2_fmt accepted {:?}", body);
+        assert!(stderr.contains("Invalid game"), "{:?}: {}", body, stderr);
+        assert!(!stderr.contains("panicked"), "{:?}: {}", body, stderr);
+    }
+}
+
+#[test]
+fn whitespace_is_optional_after_punctuation_only() {
+    let cases = [
+        ("Game 1:3 blue", true),
+        ("Game 1:3 blue,4 red;2 green", true),
+        ("Game\t1:\n3\tblue", true),
+        ("Game1: 3 blue", false),
+        ("Game 1: 3blue", false),
+        ("Game 1 : 3 blue", false),
+    ];
+    for (body, valid) in cases {
+        assert_eq!(
+            parse_input(body).is_ok(),
+            valid,
+            "parse_input on {:?}",
+            body
+        );
+        assert_eq!(
+            Games::parse(body).is_ok(),
+            valid,
+            "Games::parse on {:?}",
+            body
+        );
+        if !body.contains('\n') {
+            let reference = reference::day_2_part_2(body).is_some();
+            assert_eq!(reference, valid, "reference on {:?}", body);
+        }
+    }
+}
@@ -0,0 +1,74 @@
+//! `day_2_gen --check` on fixed seeds, unbiased and under either bias.
+
+use std::process::Command;
+
+fn check(args: &[&str]) {
+    let output = Command::new(env!("CARGO_BIN_EXE_day_2_gen"))
+        .args(["--seed", "0", "--check", "100"])
+        .args(args)
+        .output()
+        .expect("day_2_gen runs");
+    assert!(
+        output.status.success(),
+        "day_2_gen {:?} failed: {}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[test]
+fn unbiased() {
+    check(&[]);
+}
+
+#[test]
+fn possible() {
+    check(&["--bias", "possible"]);
+}
+
+#[test]
+fn impossible() {
+    check(&["--bias", "impossible"]);
+}
+
+#[test]
+fn other_colours_and_quantities() {
+    check(&["--colors", "red,yellow", "--qty", "0..30", "--sets", "1..2"]);
+    check(&[
+        "--bias",
+        "possible",
+        "--colors",
+        "red,yellow",
+        "--qty",
+        "5..30",
+    ]);
+    check(&[
+        "--bias",
+        "impossible",
+        "--colors",
+        "blue,yellow",
+        "--qty",
+        "0..3",
+    ]);
+}
+
+#[test]
+fn huge_bag() {
+    check(&["--bag", "red=4000000000,green=13,blue=14"]);
+    check(&["--bag", "red=4000000000", "--qty", "0..4000000001"]);
+}
+
+#[test]
+fn rejects_a_bias_no_colour_can_keep() {
+    for args in [
+        ["--bias", "possible", "--qty", "15..20"],
+        ["--bias", "impossible", "--qty", "1..12"],
+    ] {
+        let status = Command::new(env!("CARGO_BIN_EXE_day_2_gen"))
+            .args(args)
+            .output()
+            .expect("day_2_gen runs")
+            .status;
+        assert!(!status.success(), "day_2_gen {:?} succeeded", args);
+    }
+}
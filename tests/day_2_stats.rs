@@ -0,0 +1,82 @@
+//! The `day_2_b --stats` report over the example games.
+
+use steinerkelvin_aoc_2023::day_2::EXAMPLE;
+use steinerkelvin_aoc_2023::day_2::{parse_input, stats, Color, HISTOGRAM_WIDTH};
+
+#[test]
+fn example_colours() {
+    let stats = stats(&parse_input(EXAMPLE).unwrap());
+    let colors: Vec<(String, u32, f64, Vec<u32>)> = stats
+        .colors
+        .iter()
+        .map(|color| {
+            let name = color.color.to_string();
+            (name, color.largest, color.mean, color.binding.clone())
+        })
+        .collect();
+    assert_eq!(
+        colors,
+        [
+            ("blue".to_string(), 15, 50.0 / 11.0, vec![4]),
+            ("red".to_string(), 20, 61.0 / 11.0, vec![3]),
+            ("green".to_string(), 13, 48.0 / 13.0, vec![3]),
+        ]
+    );
+}
+
+#[test]
+fn example_set_sizes() {
+    let stats = stats(&parse_input(EXAMPLE).unwrap());
+    let sizes: Vec<(u64, usize)> = stats.set_sizes.into_iter().collect();
+    assert_eq!(
+        sizes,
+        [
+            (2, 2),
+            (3, 1),
+            (5, 1),
+            (6, 1),
+            (7, 1),
+            (8, 1),
+            (9, 2),
+            (10, 2),
+            (22, 1),
+            (32, 1),
+            (34, 1)
+        ]
+    );
+}
+
+#[test]
+fn colours_never_drawn_are_left_out() {
+    Color::intern("mauve");
+    let stats = stats(&parse_input("Game 1: 0 red\nGame 2: 2 blue").unwrap());
+    let binding: Vec<(String, Vec<u32>)> = stats
+        .colors
+        .iter()
+        .map(|color| (color.color.to_string(), color.binding.clone()))
+        .collect();
+    assert_eq!(
+        binding,
+        [("red".to_string(), vec![1]), ("blue".to_string(), vec![2])]
+    );
+}
+
+#[test]
+fn histogram_bars_are_capped() {
+    let body: String = (1..=5000)
+        .map(|id| format!("Game {}: 1 red; 1 red; 2 red\n", id))
+        .collect();
+    let report = stats(&parse_input(&body).unwrap()).to_string();
+    let histogram: Vec<&str> = report
+        .lines()
+        .skip_while(|line| *line != "Cubes per set:")
+        .skip(1)
+        .collect();
+    assert_eq!(
+        histogram,
+        [
+            format!("    1: {} 10000", "#".repeat(HISTOGRAM_WIDTH)),
+            format!("    2: {} 5000", "#".repeat(HISTOGRAM_WIDTH / 2)),
+        ]
+    );
+}
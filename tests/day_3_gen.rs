@@ -0,0 +1,43 @@
+//! `day_3_gen --check` on fixed seeds, over schematics of a few shapes and
+//! densities.
+
+use std::process::Command;
+
+fn check(args: &[&str]) {
+    let output = Command::new(env!("CARGO_BIN_EXE_day_3_gen"))
+        .args(["--seed", "0", "--check", "20"])
+        .args(args)
+        .output()
+        .expect("day_3_gen runs");
+    assert!(
+        output.status.success(),
+        "day_3_gen {:?} failed: {}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[test]
+fn puzzle_sized() {
+    check(&[]);
+}
+
+#[test]
+fn small_and_dense() {
+    check(&[
+        "--rows",
+        "6",
+        "--cols",
+        "8",
+        "--numbers",
+        "40",
+        "--symbols",
+        "30",
+    ]);
+}
+
+#[test]
+fn single_row_and_column() {
+    check(&["--rows", "1", "--cols", "30"]);
+    check(&["--rows", "30", "--cols", "1"]);
+}
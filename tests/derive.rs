@@ -0,0 +1,41 @@
+//! `#[derive(FromInput)]` on field types whose tokens could confuse the
+//! derive's field splitting.
+
+use std::marker::PhantomData;
+
+use steinerkelvin_aoc_2023::parser::{consume, parse_u64, FromInput, Parser, ParserResult};
+
+/// A number, tagged with a type that never appears in the input.
+#[derive(Debug, PartialEq)]
+struct Tagged<T>(u64, PhantomData<T>);
+
+impl<T> FromInput for Tagged<T> {
+    fn from_input(parser: Parser) -> ParserResult<Self> {
+        let (parser, value) = parse_u64(parser)?;
+        parser.with(Tagged(value, PhantomData))
+    }
+}
+
+#[derive(Debug, PartialEq, FromInput)]
+#[format("{first} -> {second}, {third}")]
+struct Arrows {
+    first: Tagged<fn(u64) -> u64>,
+    second: Tagged<fn() -> Vec<u64>>,
+    third: u64,
+}
+
+#[test]
+fn splits_fields_after_fn_pointer_types() {
+    let parsed = consume(Arrows::from_input(Parser {
+        body: "1 -> 2, 3",
+        pos: 0,
+    }));
+    assert_eq!(
+        parsed,
+        Arrows {
+            first: Tagged(1, PhantomData),
+            second: Tagged(2, PhantomData),
+            third: 3,
+        }
+    );
+}
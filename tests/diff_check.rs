@@ -0,0 +1,69 @@
+//! Every solver against its reference in `check::diff`, on a fixed set of
+//! inputs.
+
+use steinerkelvin_aoc_2023::check::diff::{disagreement, CHECKS};
+
+const SEED: u64 = 0;
+const ROUNDS: u64 = 500;
+
+fn agrees(name: &str) {
+    let check = CHECKS
+        .iter()
+        .find(|check| check.name == name)
+        .expect("known check");
+    if let Some((seed, input)) = disagreement(check, SEED, ROUNDS) {
+        panic!(
+            "{} disagrees with seed {} on {:?}: solver {:?}, reference {:?}",
+            name,
+            seed,
+            input,
+            (check.solver)(&input),
+            (check.reference)(&input)
+        );
+    }
+}
+
+#[test]
+fn day_1_part_1_chars() {
+    agrees("day 1 part 1 (chars)");
+}
+
+#[test]
+fn day_1_part_1_bytes() {
+    agrees("day 1 part 1 (bytes)");
+}
+
+#[test]
+fn day_1_part_2() {
+    agrees("day 1 part 2");
+}
+
+#[test]
+fn day_2_part_1() {
+    agrees("day 2 part 1");
+}
+
+#[test]
+fn day_2_part_2() {
+    agrees("day 2 part 2");
+}
+
+#[test]
+fn day_2_part_1_borrowed() {
+    agrees("day 2 part 1 (borrowed)");
+}
+
+#[test]
+fn day_2_part_2_borrowed() {
+    agrees("day 2 part 2 (borrowed)");
+}
+
+#[test]
+fn day_3_part_1() {
+    agrees("day 3 part 1");
+}
+
+#[test]
+fn day_3_part_2() {
+    agrees("day 3 part 2");
+}
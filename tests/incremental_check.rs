@@ -0,0 +1,85 @@
+//! The incremental answers of `check::edits` against full recomputation and
+//! the real solvers, over a fixed series of edits.
+
+use steinerkelvin_aoc_2023::check::edits::{self, Check, Options};
+use steinerkelvin_aoc_2023::check::edits::{DAY_1_PART_1, DAY_1_PART_2, DAY_2_PART_1};
+use steinerkelvin_aoc_2023::check::edits::{DAY_2_PART_2, DAY_3_PART_1, DAY_3_PART_2};
+use steinerkelvin_aoc_2023::day_2::{bag_from_str, day_2_cube_conundrum_part_2, parse_input};
+use steinerkelvin_aoc_2023::day_2::{IncrementalGames, DEFAULT_BAG};
+use steinerkelvin_aoc_2023::day_3::{day_3_gear_ratios, PartNumbers};
+use steinerkelvin_aoc_2023::incremental::{Document, Incremental};
+use steinerkelvin_aoc_2023::num::BigUint;
+
+const OPTIONS: Options = Options {
+    seed: 0,
+    rounds: 100,
+    edits: 20,
+};
+
+fn agrees<D: Document>(check: &Check<D>) {
+    let summary = edits::check(check, &OPTIONS).unwrap_or_else(|message| panic!("{}", message));
+    // Enough edits leave text with an answer for the comparison to mean much
+    assert!(
+        summary.answered * 10 >= summary.edits,
+        "{}: only {} of {} edits left an answer",
+        check.name,
+        summary.answered,
+        summary.edits
+    );
+}
+
+#[test]
+fn day_1_part_1() {
+    agrees(&DAY_1_PART_1);
+}
+
+#[test]
+fn day_1_part_2() {
+    agrees(&DAY_1_PART_2);
+}
+
+#[test]
+fn day_2_part_1() {
+    agrees(&DAY_2_PART_1);
+}
+
+#[test]
+fn day_2_part_2() {
+    agrees(&DAY_2_PART_2);
+}
+
+#[test]
+fn day_2_part_2_past_u128() {
+    let big = "4000000000 red, 4000000000 green, 4000000000 blue, 4000000000 cyan, 4000000000 pink";
+    let mut games = IncrementalGames::new(
+        bag_from_str(DEFAULT_BAG).unwrap(),
+        "Game 1: 1 red, 2 green, 3 blue, 4 cyan, 5 pink\n".to_string(),
+    );
+    games.edit(0..0, &format!("Game 2: {}\n", big)).unwrap();
+    let expected = day_2_cube_conundrum_part_2::<BigUint>(&parse_input(games.text()).unwrap());
+    assert_eq!(games.part_2::<BigUint>().ok(), expected.ok());
+    assert!(games.part_2::<u128>().is_err());
+}
+
+#[test]
+fn day_3_part_1() {
+    agrees(&DAY_3_PART_1);
+}
+
+#[test]
+fn day_3_part_2() {
+    agrees(&DAY_3_PART_2);
+}
+
+#[test]
+fn day_3_shape_before_large_numbers() {
+    for text in [
+        "99999999999999999999\n12\n",
+        "99999999999999999999.\n.\n\n1....................\n",
+    ] {
+        let incremental = Incremental::new(PartNumbers, text.to_string());
+        let answer = incremental.answer::<u64>().map_err(|err| err.to_string());
+        let expected = day_3_gear_ratios::<u64>(text).map_err(|err| err.to_string());
+        assert_eq!(answer, expected, "{:?}", text);
+    }
+}
@@ -0,0 +1,46 @@
+//! The parser properties of `check::props`, each on a fixed set of cases.
+
+use steinerkelvin_aoc_2023::check::props::{failure, PROPERTIES};
+
+const SEED: u64 = 0;
+const CASES: u64 = 2_000;
+
+fn holds(name: &str) {
+    let (_, property) = PROPERTIES
+        .iter()
+        .find(|(property, _)| *property == name)
+        .expect("known property");
+    if let Some((seed, message)) = failure(*property, SEED, CASES) {
+        panic!("{} failed with seed {}: {}", name, seed, message);
+    }
+}
+
+#[test]
+fn parse_list_round_trips() {
+    holds("parse_list round-trips");
+}
+
+#[test]
+fn exact_never_advances_on_mismatch() {
+    holds("exact never advances on mismatch");
+}
+
+#[test]
+fn consume_rejects_any_remainder() {
+    holds("consume rejects any remainder");
+}
+
+#[test]
+fn combinators_never_panic() {
+    holds("combinators never panic");
+}
+
+#[test]
+fn grammar_round_trips() {
+    holds("grammar! round-trips");
+}
+
+#[test]
+fn derived_from_input_round_trips() {
+    holds("derived FromInput round-trips");
+}
@@ -0,0 +1,55 @@
+//! Queries piped into `repl`, at the edges of what they accept.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static RUNS: AtomicUsize = AtomicUsize::new(0);
+
+/// What `repl` prints for `queries` over the example schematic of Day 3.
+fn day_3(queries: &str) -> String {
+    let run = RUNS.fetch_add(1, Ordering::Relaxed);
+    let name = format!("repl-day-3-{}-{}.txt", std::process::id(), run);
+    let path = std::env::temp_dir().join(name);
+    std::fs::write(&path, steinerkelvin_aoc_2023::day_3::EXAMPLE).unwrap();
+    let mut child = Command::new(env!("CARGO_BIN_EXE_repl"))
+        .arg("3")
+        .arg("--input")
+        .arg(&path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("repl runs");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(queries.as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().expect("repl finishes");
+    std::fs::remove_file(&path).unwrap();
+    assert!(
+        output.status.success(),
+        "repl failed on {:?}: {}",
+        queries,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn parts_near_the_largest_cell() {
+    let max = usize::MAX;
+    for cell in [(max, 0), (0, max), (max, max)] {
+        let output = day_3(&format!("parts near ({},{})\n", cell.0, cell.1));
+        assert!(output.ends_with("0 numbers\n"), "{:?}: {}", cell, output);
+    }
+}
+
+#[test]
+fn parts_near_a_corner() {
+    let output = day_3("parts near (0,0)\ngear at (3,1)\n");
+    assert!(output.contains("467 at row 0, columns 0..3, part\n1 numbers\n"));
+    assert!(output.contains("'*' next to 2 numbers (467, 35), a gear, ratio 16345\n"));
+}